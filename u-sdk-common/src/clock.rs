@@ -0,0 +1,85 @@
+//! 本地时钟和服务端时钟的偏移
+//!
+//! 签名中的时间和服务端时间相差太多（如OSS为15分钟）时，请求会返回`RequestTimeTooSkewed`等错误。
+//! 各个client持有一个[`ClockOffset`]，在发现时钟偏移时根据服务端响应的`Date`进行校正，
+//! 之后所有签名都使用校正后的时间。
+
+use reqwest::header::{DATE, HeaderMap};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, Ordering};
+use time::format_description::well_known::Rfc2822;
+use time::{Duration, OffsetDateTime};
+
+/// 本地时钟相对于服务端时钟的偏移，clone后共享同一个偏移
+#[derive(Debug, Clone, Default)]
+pub struct ClockOffset {
+    offset_ms: Arc<AtomicI64>,
+}
+
+impl ClockOffset {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 校正后的当前时间，签名时使用
+    pub fn now(&self) -> OffsetDateTime {
+        OffsetDateTime::now_utc() + self.offset()
+    }
+
+    /// 服务端时间减去本地时间
+    pub fn offset(&self) -> Duration {
+        Duration::milliseconds(self.offset_ms.load(Ordering::Relaxed))
+    }
+
+    pub fn set_offset(&self, offset: Duration) {
+        self.offset_ms
+            .store(offset.whole_milliseconds() as i64, Ordering::Relaxed);
+    }
+
+    /// 根据服务端时间更新偏移，返回新的偏移
+    pub fn sync(&self, server_time: OffsetDateTime) -> Duration {
+        let offset = server_time - OffsetDateTime::now_utc();
+        self.set_offset(offset);
+        offset
+    }
+
+    /// 根据响应的`Date` header更新偏移，header不存在或格式错误时不更新，返回`None`
+    pub fn sync_from_headers(&self, headers: &HeaderMap) -> Option<Duration> {
+        let server_time = parse_date_header(headers)?;
+        Some(self.sync(server_time))
+    }
+
+    /// 响应的`Date`和校正后的当前时间相差是否超过`max_skew`
+    pub fn is_skewed(&self, headers: &HeaderMap, max_skew: Duration) -> bool {
+        parse_date_header(headers).is_some_and(|t| (t - self.now()).abs() > max_skew)
+    }
+}
+
+fn parse_date_header(headers: &HeaderMap) -> Option<OffsetDateTime> {
+    let s = headers.get(DATE)?.to_str().ok()?;
+    OffsetDateTime::parse(s, &Rfc2822).ok()
+}
+
+#[test]
+fn clock_offset_test() {
+    use crate::helper::gmt_format;
+    use reqwest::header::HeaderValue;
+
+    let clock = ClockOffset::new();
+    let shared = clock.clone();
+    let server_time = OffsetDateTime::now_utc() + Duration::hours(1);
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        DATE,
+        HeaderValue::from_str(&gmt_format(&server_time)).unwrap(),
+    );
+
+    assert!(clock.is_skewed(&headers, Duration::minutes(15)));
+    let offset = clock.sync_from_headers(&headers).unwrap();
+    assert!((offset - Duration::hours(1)).abs() < Duration::seconds(2));
+    // clone后共享同一个偏移
+    assert!((shared.now() - server_time).abs() < Duration::seconds(2));
+    assert!(!shared.is_skewed(&headers, Duration::minutes(15)));
+
+    assert!(clock.sync_from_headers(&HeaderMap::new()).is_none());
+}
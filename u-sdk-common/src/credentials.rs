@@ -0,0 +1,142 @@
+//! 阿里云各个sdk共用的凭证和凭证提供者
+//!
+//! 使用示例见`u_sdk::credentials`
+
+use crate::error::Error;
+use crate::secret::Secret;
+use std::sync::Arc;
+use std::time::Duration;
+use time::OffsetDateTime;
+
+/// `access_key_secret`和`sts_security_token`使用[`Secret`]保存，`Debug`输出时显示为`***`
+#[derive(Clone, Debug)]
+pub struct Credentials {
+    pub access_key_id: String,
+    pub access_key_secret: Secret,
+    pub sts_security_token: Option<Secret>,
+    /// 凭证的过期时间，长期凭证为`None`
+    ///
+    /// 发送请求时不会检查这个字段，`CredentialsProvider`可以根据它决定是否需要刷新凭证，
+    /// 如`u_sdk::sts::AssumeRoleCredentialsProvider`
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+impl Credentials {
+    pub fn new(
+        access_key_id: impl Into<String>,
+        access_key_secret: impl Into<Secret>,
+        security_token: Option<String>,
+        expires_at: Option<OffsetDateTime>,
+    ) -> Self {
+        Self {
+            access_key_id: access_key_id.into(),
+            access_key_secret: access_key_secret.into(),
+            sts_security_token: security_token.map(Secret::from),
+            expires_at,
+        }
+    }
+
+    /// 凭证是否已经过期，长期凭证永远不会过期
+    pub fn is_expired(&self) -> bool {
+        self.expires_within(Duration::ZERO)
+    }
+
+    /// 凭证是否会在`duration`内过期（包括已经过期），长期凭证永远返回`false`
+    pub fn expires_within(&self, duration: Duration) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= OffsetDateTime::now_utc() + duration)
+    }
+}
+
+#[async_trait::async_trait]
+pub trait CredentialsProvider: Send + Sync {
+    async fn load(
+        &self,
+    ) -> Result<Arc<Credentials>, Box<dyn std::error::Error + Send + Sync + 'static>>;
+}
+
+/// 从`provider`加载凭证，失败时返回[`Error::Credentials`]
+///
+/// 各个sdk发送请求前都通过这个函数加载凭证
+pub async fn load_credentials(
+    provider: &dyn CredentialsProvider,
+) -> Result<Arc<Credentials>, Error> {
+    provider.load().await.map_err(Error::Credentials)
+}
+
+/// 始终返回同一个凭证的[`CredentialsProvider`]
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use u_sdk_common::credentials::{Credentials, StaticCredentialsProvider};
+///
+/// let provider = StaticCredentialsProvider::new(Credentials::new("id", "secret", None, None));
+/// let provider = Arc::new(provider);
+/// ```
+#[derive(Debug, Clone)]
+pub struct StaticCredentialsProvider {
+    creds: Arc<Credentials>,
+}
+
+impl StaticCredentialsProvider {
+    pub fn new(creds: Credentials) -> Self {
+        Self {
+            creds: Arc::new(creds),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CredentialsProvider for StaticCredentialsProvider {
+    async fn load(
+        &self,
+    ) -> Result<Arc<Credentials>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        Ok(Arc::clone(&self.creds))
+    }
+}
+
+#[test]
+fn expires_within_test() {
+    let expires_in = |d: time::Duration| {
+        Credentials::new("id", "secret", None, Some(OffsetDateTime::now_utc() + d))
+    };
+
+    let creds = expires_in(time::Duration::minutes(10));
+    assert!(!creds.is_expired());
+    assert!(!creds.expires_within(Duration::from_secs(300)));
+    assert!(creds.expires_within(Duration::from_secs(900)));
+    assert!(expires_in(time::Duration::seconds(-1)).is_expired());
+
+    let creds = Credentials::new("id", "secret", None, None);
+    assert!(!creds.is_expired());
+    assert!(!creds.expires_within(Duration::from_secs(u32::MAX as u64)));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn load_credentials_test() {
+    struct FailingProvider;
+    #[async_trait::async_trait]
+    impl CredentialsProvider for FailingProvider {
+        async fn load(
+            &self,
+        ) -> Result<Arc<Credentials>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Err("no credentials".into())
+        }
+    }
+
+    let err = load_credentials(&FailingProvider).await.unwrap_err();
+    assert!(matches!(err, Error::Credentials(_)));
+    // 原因只通过source返回，避免错误链中重复输出
+    assert_eq!(err.to_string(), "load credentials error");
+    assert_eq!(
+        std::error::Error::source(&err).unwrap().to_string(),
+        "no credentials"
+    );
+
+    let provider = StaticCredentialsProvider::new(Credentials::new("id", "secret", None, None));
+    assert_eq!(
+        load_credentials(&provider).await.unwrap().access_key_id,
+        "id"
+    );
+}
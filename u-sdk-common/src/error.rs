@@ -1,9 +1,172 @@
+//! 所有sdk模块共用的错误类型
+//!
+//! 服务端返回的非2xx响应统一解析为[`ServiceError`]，包括http状态码、错误码、错误信息和请求id，
+//! 可以使用[`Error::status`]、[`Error::code`]、[`Error::request_id`]和[`Error::is_retryable`]
+//! 统一处理各个模块返回的错误
+
+use crate::retry::classify_error;
+use reqwest::StatusCode;
+use reqwest::header::HeaderMap;
+use std::fmt;
+use std::time::Duration;
+
+/// 服务端返回的错误
+#[derive(Debug, Clone)]
+pub struct ServiceError {
+    pub status: StatusCode,
+    /// 错误码，如OpenAPI的`Throttling.User`、OSS的`NoSuchKey`，响应中没有错误码时为`None`
+    pub code: Option<String>,
+    /// 错误信息，无法解析响应时为响应的原始内容
+    pub message: String,
+    /// 请求id，向阿里云提交工单时需要提供
+    pub request_id: Option<String>,
+}
+
+impl ServiceError {
+    /// 从失败的响应中解析错误，支持OpenAPI的json和OSS的xml格式，
+    /// 请求id优先使用body中的`RequestId`，其次使用`x-acs-request-id`、`x-oss-request-id`响应头
+    pub fn parse(status: StatusCode, headers: &HeaderMap, body: &str) -> Self {
+        let (code, message, request_id) = parse_json_body(body)
+            .or_else(|| parse_xml_body(body))
+            .unwrap_or((None, None, None));
+        let request_id = request_id.or_else(|| {
+            ["x-acs-request-id", "x-oss-request-id"]
+                .iter()
+                .find_map(|name| headers.get(*name)?.to_str().ok())
+                .map(|s| s.to_owned())
+        });
+
+        Self {
+            status,
+            code,
+            message: message.unwrap_or_else(|| body.to_owned()),
+            request_id,
+        }
+    }
+}
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.status)?;
+        if let Some(code) = &self.code {
+            write!(f, ", code: {}", code)?;
+        }
+        write!(f, ", message: {}", self.message)?;
+        if let Some(request_id) = &self.request_id {
+            write!(f, ", request id: {}", request_id)?;
+        }
+        Ok(())
+    }
+}
+
+type ParsedBody = (Option<String>, Option<String>, Option<String>);
+
+fn parse_json_body(body: &str) -> Option<ParsedBody> {
+    let v = serde_json::from_str::<serde_json::Value>(body).ok()?;
+    let field = |name: &str| v.get(name)?.as_str().map(|s| s.to_owned());
+    let code = field("Code");
+    code.is_some()
+        .then(|| (code, field("Message"), field("RequestId")))
+}
+
+fn parse_xml_body(body: &str) -> Option<ParsedBody> {
+    let field = |name: &str| {
+        let (open, close) = (format!("<{}>", name), format!("</{}>", name));
+        let start = body.find(&open)? + open.len();
+        let end = body[start..].find(&close)? + start;
+        Some(body[start..end].to_owned())
+    };
+    let code = field("Code")?;
+    Some((Some(code), field("Message"), field("RequestId")))
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
+    /// 参数错误、解析响应失败等本地的错误
     #[error("error: {0}")]
     Common(String),
-    #[error("request api failed: {status}, message: {message}")]
-    RequestAPIFailed { status: String, message: String },
+    /// 服务端返回的错误
+    #[error("service error: {0}")]
+    Service(ServiceError),
     #[error("reqwest error: {0}")]
     Reqwest(#[from] reqwest::Error),
+    /// 请求或读取响应超时
+    #[error("timed out after {0:?}")]
+    Timeout(Duration),
+    /// 加载凭证失败，具体的原因通过`source`获取
+    #[error("load credentials error")]
+    Credentials(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("error: {0}")]
+    Other(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl Error {
+    /// 服务端返回的错误
+    pub fn service_error(&self) -> Option<&ServiceError> {
+        match self {
+            Error::Service(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// 响应的http状态码
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::Service(e) => Some(e.status),
+            Error::Reqwest(e) => e.status(),
+            _ => None,
+        }
+    }
+
+    /// 服务端返回的错误码
+    pub fn code(&self) -> Option<&str> {
+        self.service_error()?.code.as_deref()
+    }
+
+    /// 服务端返回的请求id
+    pub fn request_id(&self) -> Option<&str> {
+        self.service_error()?.request_id.as_deref()
+    }
+
+    /// 是否是可以重试的错误，如连接失败、限流、5xx
+    ///
+    /// client已经按照`retry_policy`进行了重试，这里用于在应用层决定是否稍后再次尝试
+    pub fn is_retryable(&self) -> bool {
+        classify_error(self).is_some()
+    }
+}
+
+#[test]
+fn service_error_test() {
+    let mut headers = HeaderMap::new();
+    headers.insert("x-oss-request-id", "oss-id".parse().unwrap());
+
+    let e = ServiceError::parse(
+        StatusCode::BAD_REQUEST,
+        &HeaderMap::new(),
+        r#"{"RequestId":"1","Code":"Throttling.User","Message":"too many"}"#,
+    );
+    assert_eq!(e.code.as_deref(), Some("Throttling.User"));
+    assert_eq!(e.message, "too many");
+    assert_eq!(e.request_id.as_deref(), Some("1"));
+
+    let e = ServiceError::parse(
+        StatusCode::NOT_FOUND,
+        &headers,
+        "<Error><Code>NoSuchKey</Code><Message>not exist</Message></Error>",
+    );
+    assert_eq!(e.code.as_deref(), Some("NoSuchKey"));
+    assert_eq!(e.message, "not exist");
+    assert_eq!(e.request_id.as_deref(), Some("oss-id"));
+
+    let e = ServiceError::parse(StatusCode::BAD_GATEWAY, &HeaderMap::new(), "bad gateway");
+    assert_eq!(e.code, None);
+    assert_eq!(e.message, "bad gateway");
+
+    let e = Error::Service(e);
+    assert_eq!(e.status(), Some(StatusCode::BAD_GATEWAY));
+    assert!(e.is_retryable());
+    assert!(!Error::Common("".to_owned()).is_retryable());
 }
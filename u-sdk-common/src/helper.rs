@@ -1,4 +1,4 @@
-use crate::Error;
+use crate::{Error, ServiceError};
 use hmac::{Hmac, Mac};
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use sha1::Sha1;
@@ -22,13 +22,19 @@ pub fn gmt_format(date_time: &OffsetDateTime) -> String {
 /// eg: 2025-11-13T13:31:09Z
 //  TODO 似乎不需要把精度设置为秒，可以直接使用默认的ISO 8601格式，测试一下到时后
 pub fn now_iso8601() -> String {
+    iso8601_format(&OffsetDateTime::now_utc())
+}
+
+/// 输出格式同[`now_iso8601`]
+pub fn iso8601_format(date_time: &OffsetDateTime) -> String {
     const ENCODED_CONFIG: EncodedConfig = Config::DEFAULT
         .set_time_precision(TimePrecision::Second {
             decimal_digits: None,
         })
         .encode();
 
-    OffsetDateTime::now_utc()
+    date_time
+        .to_utc()
         .format(&Iso8601::<ENCODED_CONFIG>)
         .unwrap()
 }
@@ -50,14 +56,12 @@ pub fn sign_hmac_sha1(secret: &str, str_to_sign: &str) -> Vec<u8> {
     mac.finalize().into_bytes().to_vec()
 }
 
+/// 读取失败响应的body，解析为[`Error::Service`]
 pub async fn into_request_failed_error(resp: reqwest::Response) -> Error {
     let status = resp.status();
-    let body = resp.text().await;
-    match body {
-        Ok(message) => Error::RequestAPIFailed {
-            status: status.to_string(),
-            message,
-        },
+    let headers = resp.headers().clone();
+    match resp.text().await {
+        Ok(body) => Error::Service(ServiceError::parse(status, &headers, &body)),
         Err(e) => Error::Reqwest(e),
     }
 }
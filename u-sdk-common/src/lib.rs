@@ -1,4 +1,11 @@
+pub mod clock;
+pub mod credentials;
 mod error;
-pub use error::Error;
+pub use error::{Error, ServiceError};
 pub mod helper;
+pub mod open_api;
 pub mod open_api_sign;
+pub mod retry;
+pub mod secret;
+pub mod timeout;
+pub mod transport;
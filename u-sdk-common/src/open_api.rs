@@ -0,0 +1,373 @@
+//! 通用的阿里云OpenAPI客户端
+//!
+//! 使用V3签名（ACS3-HMAC-SHA256）调用任意产品的RPC或ROA风格的API，
+//! 对于sdk还没有封装的API，可以直接使用[`OpenApiClient::call`]或[`OpenApiClient::request`]调用。
+//!
+//! API的action、version和参数请查看对应产品的[OpenAPI门户](https://api.aliyun.com/)
+
+use crate::clock::ClockOffset;
+use crate::credentials::{CredentialsProvider, load_credentials};
+use crate::error::{Error, ServiceError};
+use crate::helper::into_header_map;
+pub use crate::open_api_sign::{OpenApiStyle, ParamStyle, RequestBody};
+use crate::open_api_sign::{SignParams, get_openapi_request_header};
+use crate::retry::{RetryPolicy, is_idempotent_method, send_with_retry};
+use crate::secret::Secret;
+use crate::transport::{HttpTransport, new_request, transport_with_timeouts};
+use bon::{Builder, bon};
+use reqwest::{Method, Request, Url};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 通用的OpenAPI客户端
+///
+/// `OpenApiClient`的clone开销很小，clone后共享同一个`CredentialsProvider`和http连接池
+#[derive(Clone)]
+pub struct OpenApiClient {
+    credentials_provider: Arc<dyn CredentialsProvider>,
+    http_client: Arc<dyn HttpTransport>,
+    host: String,
+    style: OpenApiStyle,
+    retry_policy: RetryPolicy,
+    clock_offset: ClockOffset,
+}
+
+#[bon]
+impl OpenApiClient {
+    /// - `host`：产品的endpoint，如`sts.cn-hangzhou.aliyuncs.com`
+    /// - `style`：默认为[`OpenApiStyle::RPC`]
+    /// - `retry_policy`：重试策略，默认为[`RetryPolicy::default`]
+    /// - `http_client`：`reqwest::Client`或其它[`HttpTransport`]，默认为`reqwest::Client::new()`
+    /// - `connect_timeout`、`read_timeout`、`timeout`：连接、读取和单次请求的总超时，
+    ///   只在没有指定`http_client`时生效，默认不限制
+    #[builder(on(String, into))]
+    pub fn new(
+        credentials_provider: Arc<dyn CredentialsProvider>,
+        host: String,
+        #[builder(default = OpenApiStyle::RPC)] style: OpenApiStyle,
+        #[builder(default)] retry_policy: RetryPolicy,
+        #[builder(with = |http_client: impl HttpTransport + 'static| Arc::new(http_client))]
+        http_client: Option<Arc<dyn HttpTransport>>,
+        connect_timeout: Option<Duration>,
+        read_timeout: Option<Duration>,
+        timeout: Option<Duration>,
+    ) -> Self {
+        let http_client = http_client
+            .unwrap_or_else(|| transport_with_timeouts(connect_timeout, read_timeout, timeout));
+        Self {
+            credentials_provider,
+            http_client,
+            host,
+            style,
+            retry_policy,
+            clock_offset: ClockOffset::new(),
+        }
+    }
+}
+
+impl OpenApiClient {
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn style(&self) -> OpenApiStyle {
+        self.style
+    }
+
+    /// 返回使用`credentials_provider`的client，共享http连接池和其它配置
+    pub fn with_credentials_provider(
+        &self,
+        credentials_provider: Arc<dyn CredentialsProvider>,
+    ) -> Self {
+        Self {
+            credentials_provider,
+            ..self.clone()
+        }
+    }
+
+    /// 签名使用的时钟偏移，服务端返回时间偏移过大的错误时会自动校正
+    pub fn clock_offset(&self) -> &ClockOffset {
+        &self.clock_offset
+    }
+
+    /// 调用OpenAPI，响应的json反序列化为`T`，不需要具体类型时可以使用`serde_json::Value`
+    ///
+    /// - `action`：API名称，如`AssumeRole`
+    /// - `version`：API版本，如`2015-04-01`
+    /// - `query`：query参数，需要是序列化后为json object的类型，如struct、`HashMap`，
+    ///   数组和嵌套对象会按照签名文档展开为`Key.1`、`Key.SubKey`的形式，没有参数时传`()`
+    /// - `body`：请求体，没有时传`None`
+    ///
+    /// 需要指定参数的展开方式，或者调用带路径参数的ROA风格API时，请使用[`OpenApiClient::request`]
+    pub async fn call<T: DeserializeOwned>(
+        &self,
+        action: &str,
+        version: &str,
+        method: Method,
+        query: impl Serialize,
+        body: Option<RequestBody<'_>>,
+    ) -> Result<T, Error> {
+        let query = serde_json::to_value(query)
+            .map_err(|e| Error::Common(format!("serialize query failed: {}", e)))?;
+        self.request(action, version)
+            .method(method)
+            .query(query)
+            .maybe_body(body)
+            .build()
+            .send()
+            .await
+    }
+
+    /// 构建一个OpenAPI请求，可以设置ROA风格的路径和路径参数、query参数的展开方式
+    ///
+    /// ```ignore
+    /// let res: serde_json::Value = client
+    ///     .request("DescribeClusterDetail", "2015-12-15")
+    ///     .pathname("/clusters/{ClusterId}")
+    ///     .path_param("ClusterId", "c82e6987e2961451182edacd74faf****")
+    ///     .build()
+    ///     .send()
+    ///     .await?;
+    /// ```
+    pub fn request<'a>(&'a self, action: &'a str, version: &'a str) -> OpenApiRequestBuilder<'a> {
+        OpenApiRequest::builder(self, action, version)
+    }
+}
+
+/// 使用[`OpenApiClient::request`]构建的请求
+///
+/// - `method`：默认为GET
+/// - `pathname`：ROA风格API的资源路径，可以包含`{Name}`形式的路径参数，
+///   使用`path_param`设置参数值，默认为`/`，RPC风格时忽略
+/// - `query`：需要是json object，数组和对象参数默认按照[`ParamStyle::RepeatList`]展开，
+///   可以使用`param_style`为某个参数指定其它的展开方式
+/// - `body`：请求体
+/// - `idempotent`：请求是否幂等，用于判断服务端可能已经处理了请求时是否重试，
+///   默认根据`method`判断，GET为幂等，POST为非幂等
+/// - `anonymous`：匿名调用，不加载凭证也不添加`Authorization`，
+///   用于`AssumeRoleWithOIDC`、`AssumeRoleWithSAML`等不需要AccessKey的API，默认为`false`
+#[derive(Builder)]
+pub struct OpenApiRequest<'a> {
+    #[builder(start_fn)]
+    client: &'a OpenApiClient,
+    #[builder(start_fn)]
+    action: &'a str,
+    #[builder(start_fn)]
+    version: &'a str,
+    #[builder(field)]
+    path_params: HashMap<String, String>,
+    #[builder(field)]
+    param_styles: HashMap<String, ParamStyle>,
+    #[builder(default = Method::GET)]
+    method: Method,
+    #[builder(default = "/".to_owned(), into)]
+    pathname: String,
+    query: Option<Value>,
+    body: Option<RequestBody<'a>>,
+    idempotent: Option<bool>,
+    #[builder(default)]
+    anonymous: bool,
+}
+
+impl<S: open_api_request_builder::State> OpenApiRequestBuilder<'_, S> {
+    pub fn path_param(mut self, key: impl Into<String>, val: impl Into<String>) -> Self {
+        self.path_params.insert(key.into(), val.into());
+        self
+    }
+
+    pub fn param_style(mut self, key: impl Into<String>, style: ParamStyle) -> Self {
+        self.param_styles.insert(key.into(), style);
+        self
+    }
+}
+
+impl OpenApiRequest<'_> {
+    /// 发送请求，失败时按照client的[`RetryPolicy`]重试
+    ///
+    /// 服务端返回时间偏移过大的错误时，根据响应的`Date`校正client的时钟偏移后重新签名，并自动重试一次，
+    /// 这次重试不计入`max_attempts`
+    pub async fn send<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        let mut skew_retried = false;
+        loop {
+            let res = self.send_once().await;
+            match res {
+                Err(e) if !skew_retried && is_clock_skewed(&e) => {
+                    skew_retried = true;
+                }
+                res => return res,
+            }
+        }
+    }
+
+    async fn send_once<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        let client = self.client;
+        let idempotent = self
+            .idempotent
+            .unwrap_or_else(|| is_idempotent_method(&self.method));
+        let resp = send_with_retry(
+            &client.retry_policy,
+            client.http_client.as_ref(),
+            idempotent,
+            || self.signed_request(),
+        )
+        .await?;
+        let resp_headers = resp.headers().clone();
+
+        let res = parse_openapi_response(resp).await;
+        if let Err(e) = &res
+            && is_clock_skewed(e)
+        {
+            client.clock_offset.sync_from_headers(&resp_headers);
+        }
+        res
+    }
+
+    // 每次发送前重新加载凭证并签名
+    async fn signed_request(&self) -> Result<Request, Error> {
+        let client = self.client;
+        let creds = match self.anonymous {
+            true => None,
+            false => Some(load_credentials(client.credentials_provider.as_ref()).await?),
+        };
+
+        let empty_query = Value::Object(Default::default());
+        let sign_params = SignParams {
+            req_method: self.method.as_str(),
+            date_time: client.clock_offset.now(),
+            host: &client.host,
+            pathname: &self.pathname,
+            path_params: Some(&self.path_params),
+            query_map: self.query.as_ref().unwrap_or(&empty_query),
+            param_styles: Some(&self.param_styles),
+            x_acs_action: self.action,
+            x_acs_version: self.version,
+            x_acs_security_token: creds
+                .as_ref()
+                .and_then(|c| c.sts_security_token.as_ref())
+                .map(Secret::expose_secret),
+            request_body: self.body.as_ref(),
+            style: &client.style,
+        };
+        let (headers, url_) = match &creds {
+            Some(creds) => get_openapi_request_header(
+                creds.access_key_secret.expose_secret(),
+                &creds.access_key_id,
+                sign_params,
+            )?,
+            // 匿名调用使用相同的公共请求头，只是不需要Authorization
+            None => {
+                let (mut headers, url_) = get_openapi_request_header("", "", sign_params)?;
+                headers.remove("Authorization");
+                (headers, url_)
+            }
+        };
+
+        let url_ = Url::parse(&url_).map_err(|e| Error::Common(format!("invalid url: {}", e)))?;
+        Ok(new_request(
+            self.method.clone(),
+            url_,
+            into_header_map(headers),
+            self.body.map(|body| body.encode().1.into()),
+        ))
+    }
+}
+
+/// 解析OpenAPI的响应，失败时返回[`Error::Service`]
+pub async fn parse_openapi_response<T: DeserializeOwned>(
+    resp: reqwest::Response,
+) -> Result<T, Error> {
+    let status = resp.status();
+    let headers = resp.headers().clone();
+    let text = resp.text().await?;
+
+    if !status.is_success() {
+        return Err(Error::Service(ServiceError::parse(status, &headers, &text)));
+    }
+
+    serde_json::from_str(&text).map_err(|e| {
+        Error::Common(format!(
+            "parse response json error: {}, response text: {}",
+            e, text
+        ))
+    })
+}
+
+// 签名中的时间和服务端时间相差太多
+fn is_clock_skewed(e: &Error) -> bool {
+    matches!(
+        e.code(),
+        Some("RequestTimeTooSkewed" | "InvalidTimeStamp.Expired")
+    )
+}
+
+#[test]
+fn is_clock_skewed_test() {
+    let api_error = |status: reqwest::StatusCode, code: &str| {
+        Error::Service(ServiceError {
+            status,
+            code: Some(code.to_owned()),
+            message: "".to_owned(),
+            request_id: None,
+        })
+    };
+    assert!(is_clock_skewed(&api_error(
+        reqwest::StatusCode::BAD_REQUEST,
+        "InvalidTimeStamp.Expired"
+    )));
+    assert!(!is_clock_skewed(&api_error(
+        reqwest::StatusCode::BAD_REQUEST,
+        "Throttling.User"
+    )));
+    assert!(!is_clock_skewed(&Error::Common("".to_owned())));
+}
+
+#[test]
+fn send_future_is_send_test() {
+    // 各个sdk的请求需要能够在`tokio::spawn`中使用
+    use crate::credentials::{Credentials, StaticCredentialsProvider};
+
+    fn assert_send<T: Send>(_: T) {}
+    let client = OpenApiClient::builder()
+        .credentials_provider(Arc::new(StaticCredentialsProvider::new(Credentials::new(
+            "id", "secret", None, None,
+        ))))
+        .host("sts.cn-hangzhou.aliyuncs.com")
+        .build();
+    assert_send(client.call::<Value>("AssumeRole", "2015-04-01", Method::GET, (), None));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn call_without_query_test() {
+    use crate::credentials::{Credentials, StaticCredentialsProvider};
+    use reqwest::Response;
+
+    // 没有参数的API，query为空
+    struct MockTransport;
+    #[async_trait::async_trait]
+    impl HttpTransport for MockTransport {
+        async fn execute(&self, request: Request) -> Result<Response, Error> {
+            assert_eq!(request.url().query(), None);
+            assert_eq!(request.headers()["x-acs-action"], "GetCallerIdentity");
+            assert!(request.headers().contains_key("authorization"));
+            Ok(http::Response::new(r#"{"AccountId":"123"}"#).into())
+        }
+    }
+
+    let client = OpenApiClient::builder()
+        .credentials_provider(Arc::new(StaticCredentialsProvider::new(Credentials::new(
+            "id", "secret", None, None,
+        ))))
+        .host("sts.cn-hangzhou.aliyuncs.com")
+        .http_client(MockTransport)
+        .build();
+    let res: Value = client
+        .call("GetCallerIdentity", "2015-04-01", Method::POST, (), None)
+        .await
+        .unwrap();
+    assert_eq!(res["AccountId"], "123");
+}
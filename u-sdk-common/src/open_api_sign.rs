@@ -1,13 +1,15 @@
 use super::error::Error;
-use super::helper::now_iso8601;
+use super::helper::iso8601_format;
 use hmac::{Hmac, Mac};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
 use rand::Rng;
 use rand::distr::Alphanumeric;
 use serde::Serialize;
-use serde_json::Value;
+use serde_json::{Map, Value};
 use sha2::{Digest, Sha256};
 use std::collections::{BTreeMap, HashMap};
-use url::{Url, form_urlencoded};
+use time::OffsetDateTime;
+use url::form_urlencoded;
 
 // 阿里云签名文档链接：https://help.aliyun.com/zh/sdk/product-overview/v3-request-structure-and-signature
 
@@ -25,49 +27,122 @@ pub struct SignParams<'a, T: Serialize> {
     // 其它签名的时候需要的信息
     pub style: &'a OpenApiStyle,
     pub req_method: &'a str,
+    // 签名使用的时间，一般为`ClockOffset::now()`校正后的当前时间
+    pub date_time: OffsetDateTime,
+    // ROA风格API的资源路径，可以包含`{Name}`形式的路径参数，RPC风格时忽略
+    pub pathname: &'a str,
+    // pathname中路径参数的值，替换时会进行percentEncode
+    pub path_params: Option<&'a HashMap<String, String>>,
     // 这个字段只要是序列化为Value后是Object即可
     pub query_map: T,
+    // query中各个参数的展开方式，没有指定的参数使用`ParamStyle::RepeatList`
+    pub param_styles: Option<&'a HashMap<String, ParamStyle>>,
     pub request_body: Option<&'a RequestBody<'a>>,
 }
 
-fn replace_percent_encode(s: &str) -> String {
-    s.replace('+', "%20")
-        .replace('*', "%2A")
-        .replace("%7E", "~")
+// 签名文档要求的编码方式：除了`A-Z a-z 0-9 - _ . ~`外都进行编码，空格编码为`%20`
+const ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// 按照签名文档的规则进行percentEncode
+pub fn percent_encode(s: &str) -> String {
+    utf8_percent_encode(s, ENCODE_SET).to_string()
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OpenApiStyle {
     RPC,
     ROA,
 }
 
+/// query参数是数组或对象时的展开方式，对应OpenAPI元数据中参数的`style`
+///
+/// 以`Tag: [{"Key": "a"}, {"Key": "b"}]`为例：
+/// - `RepeatList`：`Tag.1.Key=a&Tag.2.Key=b`
+/// - `Flat`：和`RepeatList`的展开规则相同，元数据中对象类型的参数一般使用这个
+/// - `Json`：`Tag=[{"Key":"a"},{"Key":"b"}]`
+/// - `Simple`、`SpaceDelimited`、`PipeDelimited`：只用于元素为基本类型的数组，
+///   分别使用`,`、空格、`|`连接，如`Ids=1,2,3`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParamStyle {
+    #[default]
+    RepeatList,
+    Flat,
+    Json,
+    Simple,
+    SpaceDelimited,
+    PipeDelimited,
+}
+
+/// 替换ROA风格pathname中的路径参数，如`/clusters/{ClusterId}/nodes`
+///
+/// 路径参数的值会进行percentEncode（包括`/`），其它部分按`/`分段后进行percentEncode
+pub fn render_pathname(
+    pathname: &str,
+    path_params: Option<&HashMap<String, String>>,
+) -> Result<String, Error> {
+    let mut res = String::with_capacity(pathname.len());
+    let mut rest = pathname;
+    while let Some(start) = rest.find('{') {
+        push_encoded_path(&mut res, &rest[..start]);
+        let end = rest[start..].find('}').ok_or_else(|| {
+            Error::Common(format!("pathname `{}` has an unclosed `{{`", pathname))
+        })?;
+        let name = &rest[start + 1..start + end];
+        let value = path_params.and_then(|m| m.get(name)).ok_or_else(|| {
+            Error::Common(format!(
+                "path param `{}` of pathname `{}` is not provided",
+                name, pathname
+            ))
+        })?;
+        res.push_str(&percent_encode(value));
+        rest = &rest[start + end + 1..];
+    }
+    push_encoded_path(&mut res, rest);
+    if !res.starts_with('/') {
+        res.insert(0, '/');
+    }
+    Ok(res)
+}
+
+fn push_encoded_path(out: &mut String, path: &str) {
+    let encoded = path.split('/').map(percent_encode).collect::<Vec<_>>();
+    out.push_str(&encoded.join("/"));
+}
+
 /// CanonicalURI
 ///
+/// - `pathname`：已经替换过路径参数并编码后的路径，RPC风格为`/`
+/// - `query_map`：[`to_query_map`]展开后的query参数
+///
 /// return: (CanonicalURI, 完整的url用于发送http请求, CanonicalQueryString)
 pub fn generate_can_uri(
     host: &str,
-    query: &impl Serialize,
-    style: &OpenApiStyle,
-) -> Result<(String, String, String), Error> {
-    let query_map = to_query_map(query);
-    // println!("generate_can_uri query_map: {:#?}", query_map);
-    let u = Url::parse_with_params(&format!("https://{}", host), query_map)
-        .map_err(|_| Error::Common("url parsed failed!".to_owned()))?;
-    // 使用的url::Url在构建的时候已经按照规范percentEncode过了，但是和文档提的java.net.URLEncoder一样也需要替换
-    let can_uri = match style {
-        OpenApiStyle::RPC => "/".to_owned(),
-        OpenApiStyle::ROA => replace_percent_encode(u.path()),
-    };
-    // 如果API的请求参数信息包含了"in":"query"时，需要按照文档进行构造，这里的解决方法是，如果有query就进行处理
-    // 传入的query_map已经是排序好的BTreeMap，所以直接使用url::Url构造的query部分就是排序好的
-    // 这里query需要按照文档进行替换，从url::Url获取的
-    // NOTE 签名文档提到`当请求参数类型是array、object时，需要将参数值转化为带索引的键值对`，但是示例代码自己都是(&str, &str)，所以先不处理这种情况
-    let can_query_str = if let Some(s) = u.query() {
-        replace_percent_encode(s)
-    } else {
-        "".to_owned()
-    };
-    Ok((can_uri, u.to_string(), can_query_str))
+    pathname: &str,
+    query_map: &BTreeMap<String, String>,
+) -> (String, String, String) {
+    // 签名和发送请求使用同一个query字符串，保证两者的编码一致
+    // key和value都编码后再按key排序，BTreeMap中未编码的key排序结果可能不同
+    let mut pairs = query_map
+        .iter()
+        .map(|(k, v)| (percent_encode(k), percent_encode(v)))
+        .collect::<Vec<_>>();
+    pairs.sort();
+    let can_query_str = pairs
+        .into_iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    let mut url_ = format!("https://{}{}", host, pathname);
+    if !can_query_str.is_empty() {
+        url_.push('?');
+        url_.push_str(&can_query_str);
+    }
+    (pathname.to_owned(), url_, can_query_str)
 }
 
 pub struct GenerateCanHeadersRes {
@@ -80,12 +155,33 @@ pub struct GenerateCanHeadersRes {
 }
 
 /// 传入后获得的签名，在发送的时候必须要和传入的一致
+#[derive(Debug, Clone, Copy)]
 pub enum RequestBody<'a> {
     FormData(&'a [(&'a str, &'a str)]),
     Json(&'a str),
     Binary(&'a [u8]),
 }
 
+impl RequestBody<'_> {
+    /// 返回(Content-Type, 实际发送的body)，签名和发送请求时都使用这里的结果，保证两者一致
+    pub fn encode(&self) -> (&'static str, Vec<u8>) {
+        match self {
+            RequestBody::FormData(form_data) => {
+                let mut serializer = form_urlencoded::Serializer::new(String::new());
+                for (k, v) in form_data.iter() {
+                    serializer.append_pair(k, v);
+                }
+                (
+                    "application/x-www-form-urlencoded",
+                    serializer.finish().into_bytes(),
+                )
+            }
+            RequestBody::Json(s) => ("application/json", s.as_bytes().to_vec()),
+            RequestBody::Binary(bytes) => ("application/octet-stream", bytes.to_vec()),
+        }
+    }
+}
+
 // CanonicalizedHeaders
 pub fn generate_can_headers(
     host: &str,
@@ -94,6 +190,7 @@ pub fn generate_can_headers(
     x_acs_security_token: Option<&str>,
     x_acs_content_sha256: &str,
     content_type: Option<&str>,
+    date_time: &OffsetDateTime,
 ) -> GenerateCanHeadersRes {
     let mut need_signed_headers = BTreeMap::new();
     need_signed_headers.insert("host".to_owned(), host.trim().to_owned());
@@ -102,7 +199,7 @@ pub fn generate_can_headers(
         "x-acs-content-sha256".to_owned(),
         x_acs_content_sha256.to_owned(),
     );
-    let date = now_iso8601();
+    let date = iso8601_format(date_time);
     need_signed_headers.insert("x-acs-date".to_owned(), date.clone());
     need_signed_headers.insert("x-acs-signature-nonce".to_owned(), generate_nonce());
     need_signed_headers.insert("x-acs-version".to_owned(), x_acs_version.to_owned());
@@ -169,40 +266,25 @@ pub fn get_openapi_request_header<T: Serialize>(
     sign_params: SignParams<T>,
 ) -> Result<(HashMap<String, String>, String), Error> {
     // region    --- sign authorization
-    let (can_uri, url_, can_query_str) =
-        generate_can_uri(sign_params.host, &sign_params.query_map, sign_params.style)?;
+    let pathname = match sign_params.style {
+        OpenApiStyle::RPC => "/".to_owned(),
+        OpenApiStyle::ROA => render_pathname(sign_params.pathname, sign_params.path_params)?,
+    };
+    let query_map = to_query_map(&sign_params.query_map, sign_params.param_styles)?;
+    let (can_uri, url_, can_query_str) = generate_can_uri(sign_params.host, &pathname, &query_map);
 
-    let body_hash;
-    let mut content_type = None;
-    if let Some(pt) = sign_params.request_body {
-        match pt {
-            RequestBody::FormData(form_data) => {
-                content_type = Some("application/x-www-form-urlencoded");
-                let mut serializer = form_urlencoded::Serializer::new(String::new());
-                for (k, v) in form_data.iter() {
-                    serializer.append_pair(k, v);
-                }
-                // NOTE：这里有个问题，就是reqwest发送的时候对body编码使用的是serde_urlencoded，会不会和这里不一样？
-                // 要是不一样签名就会失败
-                let s = serializer.finish();
-                // 选择使用文档中的ACS3-HMAC-SHA256算法
-                // 文档对此部分的描述HashedRequestPayload，在这里这样实现：
-                // 在OpenAPI元数据中，如果API的请求参数信息包含了"in": "body"或"in": "formData"时，需通过RequestBody传递参数
-                // 如果传入的body_bytes为None则返回空字符串的sha256值，如果有body_bytes则计算其sha256值
-                body_hash = hash_sha256(Some(s.as_bytes()));
-            }
-            RequestBody::Json(s) => {
-                content_type = Some("application/json");
-                body_hash = hash_sha256(Some(s.as_bytes()));
-            }
-            RequestBody::Binary(bytes) => {
-                content_type = Some("application/octet-stream");
-                body_hash = hash_sha256(Some(bytes));
-            }
-        };
-    } else {
-        body_hash = hash_sha256(None);
-    }
+    // 选择使用文档中的ACS3-HMAC-SHA256算法
+    // 文档对此部分的描述HashedRequestPayload，在这里这样实现：
+    // 在OpenAPI元数据中，如果API的请求参数信息包含了"in": "body"或"in": "formData"时，需通过RequestBody传递参数
+    // 如果没有body则返回空字符串的sha256值，如果有body则计算其sha256值
+    // 发送form data时需要使用`RequestBody::encode`的结果作为body，否则签名会失败
+    let (content_type, body_hash) = match sign_params.request_body {
+        Some(body) => {
+            let (content_type, bytes) = body.encode();
+            (Some(content_type), hash_sha256(Some(&bytes)))
+        }
+        None => (None, hash_sha256(None)),
+    };
 
     let generate_can_headers_res = generate_can_headers(
         sign_params.host,
@@ -211,6 +293,7 @@ pub fn get_openapi_request_header<T: Serialize>(
         sign_params.x_acs_security_token,
         &body_hash,
         content_type,
+        &sign_params.date_time,
     );
 
     let can_req_str = format!(
@@ -242,45 +325,93 @@ pub fn get_openapi_request_header<T: Serialize>(
 
 /// 按照签名文档要求序列化请求参数为BTreeMap<String, String>
 ///
-/// 传入的query需要是一个to_value后为Value::Object的类型
+/// 传入的query需要是一个to_value后为Value::Object的类型，否则返回错误；序列化为null时（如`()`）视为没有参数
 ///
-/// 这个方法会对传入的结构体进行扁平化处理，生成符合签名要求的键值对
+/// 数组和对象类型的参数按照`param_styles`中指定的[`ParamStyle`]展开，没有指定时使用`RepeatList`，值为null的参数会被忽略
 ///
 /// 需要注意如果传入的是一个String，但是这个String本身是一个Struct序列化来的，那么不能直接传Struct，否则会被打平
-fn to_query_map(query: impl Serialize) -> BTreeMap<String, String> {
-    let v = serde_json::to_value(query).expect("Serialize to serde_json::Value failed");
-    if !v.is_object() {
-        panic!("to_query_map: input query is not an object!");
-    }
-    flatten_root(&v)
-}
+pub fn to_query_map(
+    query: impl Serialize,
+    param_styles: Option<&HashMap<String, ParamStyle>>,
+) -> Result<BTreeMap<String, String>, Error> {
+    let v = serde_json::to_value(query)
+        .map_err(|e| Error::Common(format!("serialize query failed: {}", e)))?;
+    let map = match v {
+        Value::Object(map) => map,
+        // `()`、`None`表示没有参数
+        Value::Null => Map::new(),
+        v => {
+            return Err(Error::Common(format!(
+                "query must be serialized to a json object, got: {}",
+                v
+            )));
+        }
+    };
 
-fn flatten_root(v: &Value) -> BTreeMap<String, String> {
     let mut res = BTreeMap::new();
-
-    if let Value::Object(map) = v {
-        for (k, val) in map {
-            flatten_with_prefix(k, val, &mut res);
+    for (k, val) in &map {
+        let style = param_styles
+            .and_then(|m| m.get(k))
+            .copied()
+            .unwrap_or_default();
+        match style {
+            ParamStyle::RepeatList | ParamStyle::Flat => flatten_with_prefix(k, val, &mut res),
+            ParamStyle::Json => {
+                if !val.is_null() {
+                    res.insert(k.clone(), val.to_string());
+                }
+            }
+            ParamStyle::Simple => join_array(k, val, ",", &mut res)?,
+            ParamStyle::SpaceDelimited => join_array(k, val, " ", &mut res)?,
+            ParamStyle::PipeDelimited => join_array(k, val, "|", &mut res)?,
         }
     }
 
-    res
+    Ok(res)
+}
+
+fn scalar_to_string(v: &Value) -> Option<String> {
+    match v {
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+fn join_array(
+    key: &str,
+    v: &Value,
+    sep: &str,
+    out: &mut BTreeMap<String, String>,
+) -> Result<(), Error> {
+    let items = match v {
+        Value::Null => return Ok(()),
+        Value::Array(arr) => arr
+            .iter()
+            .map(|item| {
+                scalar_to_string(item).ok_or_else(|| {
+                    Error::Common(format!(
+                        "param `{}` must be an array of scalars to be joined by `{}`",
+                        key, sep
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        v => vec![scalar_to_string(v).ok_or_else(|| {
+            Error::Common(format!(
+                "param `{}` must be an array of scalars to be joined by `{}`",
+                key, sep
+            ))
+        })?],
+    };
+    out.insert(key.to_owned(), items.join(sep));
+    Ok(())
 }
 
 fn flatten_with_prefix(prefix: &str, v: &Value, out: &mut BTreeMap<String, String>) {
     match v {
-        Value::Null => {
-            out.insert(prefix.to_owned(), "".to_owned());
-        }
-        Value::Bool(b) => {
-            out.insert(prefix.to_owned(), b.to_string());
-        }
-        Value::Number(n) => {
-            out.insert(prefix.to_owned(), n.to_string());
-        }
-        Value::String(s) => {
-            out.insert(prefix.to_owned(), s.clone());
-        }
+        Value::Null => {}
         Value::Array(arr) => {
             for (i, item) in arr.iter().enumerate() {
                 // 下标从 1 开始："Tag.1"、"Tag.2"…
@@ -294,5 +425,70 @@ fn flatten_with_prefix(prefix: &str, v: &Value, out: &mut BTreeMap<String, Strin
                 flatten_with_prefix(&new_prefix, val, out);
             }
         }
+        v => {
+            if let Some(s) = scalar_to_string(v) {
+                out.insert(prefix.to_owned(), s);
+            }
+        }
     }
 }
+
+#[test]
+fn to_query_map_test() {
+    let query = serde_json::json!({
+        "RegionId": "cn-hangzhou",
+        "Tag": [{"Key": "a", "Value": "1"}, {"Key": "b"}],
+        "Filter": {"Name": "x", "Ids": [1, 2]},
+        "Config": {"Enable": true},
+        "Ids": ["i-1", "i-2"],
+        "Empty": null,
+    });
+    let mut styles = HashMap::new();
+    styles.insert("Config".to_owned(), ParamStyle::Json);
+    styles.insert("Ids".to_owned(), ParamStyle::Simple);
+    styles.insert("Filter".to_owned(), ParamStyle::Flat);
+    let map = to_query_map(&query, Some(&styles)).unwrap();
+
+    let expected = [
+        ("Config", r#"{"Enable":true}"#),
+        ("Filter.Ids.1", "1"),
+        ("Filter.Ids.2", "2"),
+        ("Filter.Name", "x"),
+        ("Ids", "i-1,i-2"),
+        ("RegionId", "cn-hangzhou"),
+        ("Tag.1.Key", "a"),
+        ("Tag.1.Value", "1"),
+        ("Tag.2.Key", "b"),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_owned(), v.to_owned()))
+    .collect::<BTreeMap<_, _>>();
+    assert_eq!(map, expected);
+
+    assert!(to_query_map(["a", "b"], None).is_err());
+    assert!(to_query_map((), None).unwrap().is_empty());
+    let mut styles = HashMap::new();
+    styles.insert("Tag".to_owned(), ParamStyle::PipeDelimited);
+    assert!(to_query_map(&query, Some(&styles)).is_err());
+}
+
+#[test]
+fn can_uri_test() {
+    let mut params = HashMap::new();
+    params.insert("ClusterId".to_owned(), "c 1/2".to_owned());
+    let pathname = render_pathname("/clusters/{ClusterId}/nodes", Some(&params)).unwrap();
+    assert_eq!(pathname, "/clusters/c%201%2F2/nodes");
+    assert!(render_pathname("/clusters/{Id}", Some(&params)).is_err());
+    assert!(render_pathname("/clusters/{ClusterId", Some(&params)).is_err());
+
+    let mut query = BTreeMap::new();
+    query.insert("Name".to_owned(), "a b*~+".to_owned());
+    query.insert("Key".to_owned(), "中".to_owned());
+    let (can_uri, url_, can_query_str) = generate_can_uri("cs.aliyuncs.com", &pathname, &query);
+    assert_eq!(can_uri, "/clusters/c%201%2F2/nodes");
+    assert_eq!(can_query_str, "Key=%E4%B8%AD&Name=a%20b%2A~%2B");
+    assert_eq!(
+        url_,
+        "https://cs.aliyuncs.com/clusters/c%201%2F2/nodes?Key=%E4%B8%AD&Name=a%20b%2A~%2B"
+    );
+}
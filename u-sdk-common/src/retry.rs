@@ -0,0 +1,315 @@
+//! 统一的重试策略
+//!
+//! 各个client通过builder的`retry_policy`设置[`RetryPolicy`]，请求失败时按照以下规则重试：
+//! - 服务端没有处理请求的错误（连接失败、429、`Throttling*`、OSS的`RequestTimeout`）对所有请求都会重试
+//! - 服务端可能已经处理了请求的错误（5xx、读取响应超时）默认只对幂等的请求重试，
+//!   可以通过`retry_non_idempotent`对非幂等的请求也进行重试
+//! - 响应中有`Retry-After`时按照它等待，否则使用带随机抖动的指数退避
+
+use crate::error::{Error, ServiceError};
+use crate::transport::HttpTransport;
+use bon::Builder;
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Method, Request, Response, ResponseBuilderExt, StatusCode};
+use std::time::Duration;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc2822;
+
+/// 重试策略
+///
+/// - `max_attempts`：最多发送请求的次数（包括第一次），默认为3，为1时不重试
+/// - `initial_backoff`：第一次重试前的等待时间，之后每次翻倍，默认为200ms
+/// - `max_backoff`：最长的等待时间，也是`Retry-After`的上限，默认为10s
+/// - `retry_non_idempotent`：服务端可能已经处理请求时，是否也重试非幂等的请求，默认为false
+#[derive(Debug, Clone, Builder)]
+pub struct RetryPolicy {
+    #[builder(default = 3)]
+    max_attempts: u32,
+    #[builder(default = Duration::from_millis(200))]
+    initial_backoff: Duration,
+    #[builder(default = Duration::from_secs(10))]
+    max_backoff: Duration,
+    #[builder(default)]
+    retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// 可以重试的失败类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryKind {
+    /// 服务端没有处理请求，重试不会导致请求被重复执行
+    NotProcessed,
+    /// 服务端可能已经处理了请求，只对幂等的请求重试
+    MaybeProcessed,
+}
+
+impl RetryPolicy {
+    /// 不进行重试
+    pub fn disabled() -> Self {
+        Self::builder().max_attempts(1).build()
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// 第`attempt`次请求（从1开始）失败后是否需要重试
+    pub fn should_retry(&self, attempt: u32, kind: RetryKind, idempotent: bool) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+        match kind {
+            RetryKind::NotProcessed => true,
+            RetryKind::MaybeProcessed => idempotent || self.retry_non_idempotent,
+        }
+    }
+
+    /// 第`attempt`次请求（从1开始）失败后，重试前的等待时间
+    ///
+    /// 有`retry_after`时使用它（不超过`max_backoff`），否则为`initial_backoff * 2^(attempt-1)`，
+    /// 并在它的一半到全部之间随机取值，避免多个客户端同时重试
+    pub fn backoff(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(retry_after) = retry_after {
+            return retry_after.min(self.max_backoff);
+        }
+        let exp = attempt.saturating_sub(1).min(31);
+        let base = self
+            .initial_backoff
+            .saturating_mul(1 << exp)
+            .min(self.max_backoff);
+        let half = base / 2;
+        half + half.mul_f64(rand::rng().random::<f64>())
+    }
+}
+
+/// GET、HEAD、PUT、DELETE、OPTIONS为幂等的方法
+pub fn is_idempotent_method(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+    )
+}
+
+/// 根据响应的状态码判断是否可以重试
+pub fn classify_status(status: StatusCode) -> Option<RetryKind> {
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => Some(RetryKind::NotProcessed),
+        StatusCode::INTERNAL_SERVER_ERROR
+        | StatusCode::BAD_GATEWAY
+        | StatusCode::SERVICE_UNAVAILABLE
+        | StatusCode::GATEWAY_TIMEOUT => Some(RetryKind::MaybeProcessed),
+        _ => None,
+    }
+}
+
+/// 根据OpenAPI或OSS返回的错误码判断是否可以重试
+pub fn classify_code(code: &str) -> Option<RetryKind> {
+    if code.starts_with("Throttling") || code == "RequestTimeout" {
+        Some(RetryKind::NotProcessed)
+    } else if code == "ServiceUnavailable" || code == "InternalError" {
+        Some(RetryKind::MaybeProcessed)
+    } else {
+        None
+    }
+}
+
+/// 根据发送请求时的错误判断是否可以重试
+pub fn classify_error(e: &Error) -> Option<RetryKind> {
+    match e {
+        Error::Reqwest(e) if e.is_connect() => Some(RetryKind::NotProcessed),
+        Error::Reqwest(e) if e.is_timeout() => Some(RetryKind::MaybeProcessed),
+        Error::Service(e) => e
+            .code
+            .as_deref()
+            .and_then(classify_code)
+            .or_else(|| classify_status(e.status)),
+        _ => None,
+    }
+}
+
+/// 解析`Retry-After`，支持秒数和HTTP日期两种格式
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let s = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = s.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = OffsetDateTime::parse(s, &Rfc2822).ok()?;
+    let secs = (date - OffsetDateTime::now_utc()).whole_seconds().max(0);
+    Some(Duration::from_secs(secs as u64))
+}
+
+/// 按照重试策略发送请求
+///
+/// - `idempotent`：请求是否幂等，一般使用[`is_idempotent_method`]判断
+/// - `make_request`：每次发送前调用，用于重新签名和重新构建请求体（如重新打开上传的文件）
+///
+/// 返回最后一次请求的响应，调用者仍然需要检查响应的状态码。
+/// 非2xx的响应会读取body判断错误码，不重试时会使用读取到的内容重新构建响应（保留状态码、header和url）
+///
+/// `make_request`可以返回各个sdk模块自己的错误类型
+pub async fn send_with_retry<E, F>(
+    policy: &RetryPolicy,
+    transport: &dyn HttpTransport,
+    idempotent: bool,
+    mut make_request: impl FnMut() -> F,
+) -> Result<Response, E>
+where
+    E: From<Error>,
+    F: Future<Output = Result<Request, E>>,
+{
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let request = make_request().await?;
+        let (kind, wait_hint, res) = match transport.execute(request).await {
+            Ok(resp) if resp.status().is_success() || resp.status().is_redirection() => {
+                return Ok(resp);
+            }
+            Ok(resp) => {
+                let (kind, retry_after, resp) = classify_response(resp).await?;
+                (kind, retry_after, Ok(resp))
+            }
+            Err(e) => (classify_error(&e), None, Err(e)),
+        };
+
+        match kind {
+            Some(kind) if policy.should_retry(attempt, kind, idempotent) => {
+                tokio::time::sleep(policy.backoff(attempt, wait_hint)).await;
+            }
+            _ => return res.map_err(E::from),
+        }
+    }
+}
+
+// 读取失败响应的body判断是否可以重试，并用读取到的内容重新构建响应
+async fn classify_response(
+    resp: Response,
+) -> Result<(Option<RetryKind>, Option<Duration>, Response), Error> {
+    let status = resp.status();
+    let version = resp.version();
+    let url = resp.url().clone();
+    let headers = resp.headers().clone();
+    let body = resp.bytes().await?;
+
+    let kind = std::str::from_utf8(&body)
+        .ok()
+        .and_then(|body| ServiceError::parse(status, &headers, body).code)
+        .and_then(|code| classify_code(&code))
+        .or_else(|| classify_status(status));
+    let retry_after = retry_after(&headers);
+
+    // `reqwest::Response`由`http::Response`转换而来时，url需要通过`ResponseBuilderExt`设置，
+    // 否则`Response::url`会返回一个占位的地址
+    let mut rebuilt = http::Response::builder()
+        .status(status)
+        .version(version)
+        .url(url)
+        .body(body)
+        .map_err(|e| Error::Common(format!("rebuild response failed: {}", e)))?;
+    *rebuilt.headers_mut() = headers;
+    Ok((kind, retry_after, rebuilt.into()))
+}
+
+#[test]
+fn retry_policy_test() {
+    let policy = RetryPolicy::default();
+    assert!(policy.should_retry(1, RetryKind::NotProcessed, false));
+    assert!(!policy.should_retry(1, RetryKind::MaybeProcessed, false));
+    assert!(policy.should_retry(2, RetryKind::MaybeProcessed, true));
+    assert!(!policy.should_retry(3, RetryKind::NotProcessed, true));
+    assert!(!RetryPolicy::disabled().should_retry(1, RetryKind::NotProcessed, true));
+
+    for attempt in 1..=10 {
+        let backoff = policy.backoff(attempt, None);
+        let base = Duration::from_millis(200 * 2u64.pow(attempt - 1)).min(Duration::from_secs(10));
+        assert!(backoff >= base / 2 && backoff <= base);
+    }
+    assert_eq!(
+        policy.backoff(1, Some(Duration::from_secs(60))),
+        Duration::from_secs(10)
+    );
+
+    assert_eq!(
+        classify_code("Throttling.User"),
+        Some(RetryKind::NotProcessed)
+    );
+    assert_eq!(
+        classify_code("RequestTimeout"),
+        Some(RetryKind::NotProcessed)
+    );
+    assert_eq!(classify_code("InvalidParameter"), None);
+    assert_eq!(
+        classify_status(StatusCode::SERVICE_UNAVAILABLE),
+        Some(RetryKind::MaybeProcessed)
+    );
+    assert_eq!(classify_status(StatusCode::NOT_FOUND), None);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(RETRY_AFTER, "3".parse().unwrap());
+    assert_eq!(retry_after(&headers), Some(Duration::from_secs(3)));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn send_with_retry_test() {
+    use std::sync::Mutex;
+
+    // 依次返回预设的状态码和body，记录收到的请求数
+    struct MockTransport {
+        responses: Mutex<Vec<(u16, &'static str)>>,
+    }
+    #[async_trait::async_trait]
+    impl HttpTransport for MockTransport {
+        async fn execute(&self, request: Request) -> Result<Response, Error> {
+            let (status, body) = self.responses.lock().unwrap().remove(0);
+            let resp = http::Response::builder()
+                .status(status)
+                .url(request.url().clone())
+                .body(body)
+                .unwrap();
+            Ok(resp.into())
+        }
+    }
+    let mock = |responses: Vec<(u16, &'static str)>| MockTransport {
+        responses: Mutex::new(responses),
+    };
+    let policy = RetryPolicy::builder()
+        .initial_backoff(Duration::from_millis(1))
+        .build();
+    let request = || async {
+        Ok::<_, Error>(Request::new(
+            Method::POST,
+            "http://localhost/".parse().unwrap(),
+        ))
+    };
+
+    // 被限流的请求即使不是幂等的也会重试
+    let transport = mock(vec![(400, r#"{"Code":"Throttling.User"}"#), (200, "ok")]);
+    let resp = send_with_retry(&policy, &transport, false, request)
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // 非幂等的请求遇到5xx不重试，返回的响应可以再次读取body，url不变
+    let transport = mock(vec![(503, "unavailable"), (200, "ok")]);
+    let resp = send_with_retry(&policy, &transport, false, request)
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(resp.url().as_str(), "http://localhost/");
+    assert_eq!(resp.text().await.unwrap(), "unavailable");
+
+    // 幂等的请求最多发送max_attempts次
+    let transport = mock(vec![(500, ""), (502, ""), (504, ""), (200, "ok")]);
+    let resp = send_with_retry(&policy, &transport, true, request)
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::GATEWAY_TIMEOUT);
+    assert_eq!(transport.responses.lock().unwrap().len(), 1);
+}
@@ -0,0 +1,72 @@
+//! 敏感信息（AccessKey Secret、STS token、API key等）的包装类型
+
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+use zeroize::Zeroize;
+
+/// 敏感的字符串，如AccessKey Secret、STS token、API key
+///
+/// - `Debug`和`Display`都输出`***`，避免打印client配置或凭证时泄露到日志中
+/// - drop时把内存清零
+/// - 需要使用原始值时调用[`Secret::expose_secret`]
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(secret: impl Into<String>) -> Self {
+        Self(secret.into())
+    }
+
+    /// 返回原始值，注意不要把它打印到日志中
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Secret {
+    fn from(secret: String) -> Self {
+        Self(secret)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(secret: &str) -> Self {
+        Self(secret.to_owned())
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self)
+    }
+}
+
+#[test]
+fn secret_test() {
+    let secret = Secret::from("secret");
+    assert_eq!(format!("{:?}", secret), "***");
+    assert_eq!(secret.to_string(), "***");
+    assert_eq!(format!("{:?}", Some(secret.clone())), "Some(***)");
+    assert_eq!(secret.expose_secret(), "secret");
+
+    let secret: Secret = serde_json::from_str(r#""token""#).unwrap();
+    assert_eq!(secret.expose_secret(), "token");
+}
@@ -0,0 +1,136 @@
+//! 超时设置
+//!
+//! - 连接超时、读取超时、总超时：在构建client时设置，用于构建默认的`reqwest::Client`，
+//!   传入了自定义的`http_client`时请在`http_client`上设置
+//! - 单次操作的超时：[`with_timeout`]，包括重试和读取响应的时间
+//! - 流式响应的空闲超时：[`idle_timeout`]，等待下一条数据的时间超过设置时返回[`Error::Timeout`]并结束流，
+//!   调用者处理数据的时间不计入
+
+use crate::error::Error;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+use std::time::Duration;
+use tokio::time::{Instant, Sleep, sleep};
+use tokio_stream::Stream;
+
+/// 在`timeout`内完成`fut`，超时时返回[`Error::Timeout`]，`timeout`为`None`时不限制
+pub async fn with_timeout<T, E: From<Error>>(
+    timeout: Option<Duration>,
+    fut: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    match timeout {
+        Some(t) => tokio::time::timeout(t, fut)
+            .await
+            .map_err(|_| Error::Timeout(t))?,
+        None => fut.await,
+    }
+}
+
+/// 为流式响应设置空闲超时，`timeout`为`None`时不限制
+pub fn idle_timeout<S, T, E>(stream: S, timeout: Option<Duration>) -> IdleTimeout<S>
+where
+    S: Stream<Item = Result<T, E>>,
+    E: From<Error>,
+{
+    IdleTimeout {
+        stream: Box::pin(stream),
+        timeout,
+        sleep: timeout.map(|t| Box::pin(sleep(t))),
+        waiting: false,
+        done: false,
+    }
+}
+
+/// [`idle_timeout`]返回的流
+pub struct IdleTimeout<S> {
+    stream: Pin<Box<S>>,
+    timeout: Option<Duration>,
+    sleep: Option<Pin<Box<Sleep>>>,
+    // 是否已经开始等待下一条数据，调用者处理上一条数据的时间不计入空闲时间
+    waiting: bool,
+    done: bool,
+}
+
+impl<S, T, E> Stream for IdleTimeout<S>
+where
+    S: Stream<Item = Result<T, E>>,
+    E: From<Error>,
+{
+    type Item = Result<T, E>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+
+        if let Poll::Ready(item) = self.stream.as_mut().poll_next(cx) {
+            if item.is_none() {
+                self.done = true;
+            }
+            self.waiting = false;
+            return Poll::Ready(item);
+        }
+
+        let Some(t) = self.timeout else {
+            return Poll::Pending;
+        };
+        // 返回数据后第一次没有数据可读时才开始计时
+        let start_waiting = !self.waiting;
+        self.waiting = true;
+        if let Some(sleep) = self.sleep.as_mut() {
+            if start_waiting {
+                sleep.as_mut().reset(Instant::now() + t);
+            }
+            ready!(sleep.as_mut().poll(cx));
+        }
+        self.done = true;
+        Poll::Ready(Some(Err(Error::Timeout(t).into())))
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn idle_timeout_test() {
+    use tokio_stream::StreamExt;
+
+    let fast = tokio_stream::iter([Ok::<_, Error>(1), Ok(2)]);
+    let items: Vec<_> = idle_timeout(fast, Some(Duration::from_millis(50)))
+        .collect()
+        .await;
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|i| i.is_ok()));
+
+    // 第一条数据之后不再有数据
+    let stalled =
+        tokio_stream::iter([Ok::<_, Error>(1)]).chain(tokio_stream::pending::<Result<_, _>>());
+    let items: Vec<_> = idle_timeout(stalled, Some(Duration::from_millis(50)))
+        .collect()
+        .await;
+    assert_eq!(items.len(), 2);
+    assert!(matches!(items[1], Err(Error::Timeout(_))));
+
+    let res: Result<(), Error> = with_timeout(Some(Duration::from_millis(10)), async {
+        sleep(Duration::from_secs(5)).await;
+        Ok(())
+    })
+    .await;
+    assert!(matches!(res, Err(Error::Timeout(_))));
+}
+
+#[cfg(test)]
+#[tokio::test(start_paused = true)]
+async fn idle_timeout_slow_consumer_test() {
+    use tokio_stream::StreamExt;
+
+    // 每120ms产生一条数据，调用者每次处理数据需要100ms，超过了50ms的空闲超时，
+    // 但是每次等待数据的时间只有20ms，不应该超时
+    let stream =
+        tokio_stream::iter([Ok::<_, Error>(1), Ok(2), Ok(3)]).throttle(Duration::from_millis(120));
+    let mut stream = idle_timeout(stream, Some(Duration::from_millis(50)));
+    let mut items = vec![];
+    while let Some(item) = stream.next().await {
+        items.push(item.unwrap());
+        sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(items, [1, 2, 3]);
+}
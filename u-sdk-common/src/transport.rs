@@ -0,0 +1,88 @@
+//! 可替换的http传输层
+//!
+//! 各个sdk的client默认使用`reqwest::Client::new()`发送请求，可以在构建client时通过`http_client`传入：
+//! - 自定义的`reqwest::Client`，用于设置超时、代理、根证书、连接池大小，或在多个client之间共享连接池
+//! - 实现了[`HttpTransport`]的其它类型，如测试时使用的mock
+
+use crate::error::Error;
+use async_trait::async_trait;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderValue};
+use reqwest::{Body, Method, Request, Response, Url};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 发送http请求
+///
+/// 传入的`Request`已经完成签名，实现时不能修改参与签名的header、url和body
+#[async_trait]
+pub trait HttpTransport: Send + Sync {
+    async fn execute(&self, request: Request) -> Result<Response, Error>;
+}
+
+#[async_trait]
+impl HttpTransport for reqwest::Client {
+    async fn execute(&self, request: Request) -> Result<Response, Error> {
+        Ok(reqwest::Client::execute(self, request).await?)
+    }
+}
+
+#[async_trait]
+impl<T: HttpTransport + ?Sized> HttpTransport for Arc<T> {
+    async fn execute(&self, request: Request) -> Result<Response, Error> {
+        (**self).execute(request).await
+    }
+}
+
+/// client没有指定`http_client`时使用的传输层
+pub fn default_transport() -> Arc<dyn HttpTransport> {
+    Arc::new(reqwest::Client::new())
+}
+
+/// client没有指定`http_client`时，根据超时设置构建传输层
+///
+/// - `connect_timeout`：建立连接的超时
+/// - `read_timeout`：每次读取响应数据的超时
+/// - `timeout`：单次请求的总超时，从发送请求到读取完响应
+pub fn transport_with_timeouts(
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+) -> Arc<dyn HttpTransport> {
+    if connect_timeout.is_none() && read_timeout.is_none() && timeout.is_none() {
+        return default_transport();
+    }
+
+    let mut builder = reqwest::Client::builder();
+    if let Some(t) = connect_timeout {
+        builder = builder.connect_timeout(t);
+    }
+    if let Some(t) = read_timeout {
+        builder = builder.read_timeout(t);
+    }
+    if let Some(t) = timeout {
+        builder = builder.timeout(t);
+    }
+    Arc::new(builder.build().expect("build reqwest client failed"))
+}
+
+/// 构建一个`reqwest::Request`，用于[`HttpTransport::execute`]
+pub fn new_request(method: Method, url: Url, headers: HeaderMap, body: Option<Body>) -> Request {
+    let mut request = Request::new(method, url);
+    *request.headers_mut() = headers;
+    *request.body_mut() = body;
+    request
+}
+
+/// 构建一个body为json的请求，会设置`Content-Type: application/json`
+pub fn new_json_request(
+    method: Method,
+    url: Url,
+    mut headers: HeaderMap,
+    body: &impl Serialize,
+) -> Result<Request, Error> {
+    let body = serde_json::to_vec(body)
+        .map_err(|e| Error::Common(format!("serialize request body failed: {}", e)))?;
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    Ok(new_request(method, url, headers, Some(body.into())))
+}
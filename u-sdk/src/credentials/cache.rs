@@ -0,0 +1,72 @@
+use super::Credentials;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use tokio::sync::Mutex;
+use u_sdk_common::Error;
+
+/// 需要刷新的临时凭证的缓存
+///
+/// - 在过期前`refresh_margin`开始刷新
+/// - 多个请求同时需要刷新时，只会刷新一次，其它请求等待并使用刷新后的凭证
+/// - 刷新遇到可重试的错误（如限流、5xx、网络错误）且缓存的凭证还没有过期时，继续使用缓存的凭证
+pub(crate) struct CredentialsCache {
+    refresh_margin: Duration,
+    cached: RwLock<Option<Arc<Credentials>>>,
+    refresh_lock: Mutex<()>,
+}
+
+impl CredentialsCache {
+    pub(crate) fn new(refresh_margin: Duration) -> Self {
+        Self {
+            refresh_margin,
+            cached: RwLock::new(None),
+            refresh_lock: Mutex::new(()),
+        }
+    }
+
+    /// 返回缓存的凭证，需要刷新时使用`refresh`获取新的凭证
+    ///
+    /// `now`为当前时间，用于判断凭证是否过期
+    pub(crate) async fn get_or_refresh(
+        &self,
+        now: impl Fn() -> OffsetDateTime,
+        refresh: impl Future<Output = Result<Credentials, Error>>,
+    ) -> Result<Arc<Credentials>, Error> {
+        if let Some(creds) = self.cached(now(), self.refresh_margin) {
+            return Ok(creds);
+        }
+
+        let _guard = self.refresh_lock.lock().await;
+        // 等待锁的期间其它请求可能已经刷新了凭证
+        if let Some(creds) = self.cached(now(), self.refresh_margin) {
+            return Ok(creds);
+        }
+
+        match refresh.await {
+            Ok(creds) => {
+                let creds = Arc::new(creds);
+                *self.cached.write().unwrap() = Some(creds.clone());
+                Ok(creds)
+            }
+            Err(e) if e.is_retryable() => self.cached(now(), Duration::ZERO).ok_or(e),
+            Err(e) => Err(e),
+        }
+    }
+
+    // 缓存的凭证，`margin`内过期的凭证视为需要刷新
+    fn cached(&self, now: OffsetDateTime, margin: Duration) -> Option<Arc<Credentials>> {
+        let cached = self.cached.read().unwrap().clone()?;
+        match cached.expires_at {
+            Some(expires_at) if expires_at <= now + margin => None,
+            _ => Some(cached),
+        }
+    }
+}
+
+/// 解析STS、ECS元数据服务返回的过期时间，如`2015-04-09T11:52:19Z`
+pub(crate) fn parse_expiration(s: &str) -> Result<OffsetDateTime, Error> {
+    OffsetDateTime::parse(s, &Rfc3339)
+        .map_err(|e| Error::Common(format!("invalid expiration `{}`: {}", s, e)))
+}
@@ -0,0 +1,180 @@
+use super::{
+    CliProfileCredentialsProvider, Credentials, CredentialsProvider, EcsRamRoleCredentialsProvider,
+    EnvCredentialsProvider, OidcRoleCredentialsProvider,
+};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use u_sdk_common::Error;
+
+// 所有来源都失败后，在这段时间内直接返回上次的错误
+const FAILURE_TTL: Duration = Duration::from_secs(30);
+
+/// [`DefaultCredentialsChain`]中的凭证来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialsSource {
+    /// [`EnvCredentialsProvider`]
+    Env,
+    /// [`OidcRoleCredentialsProvider`]
+    Oidc,
+    /// [`CliProfileCredentialsProvider`]
+    CliProfile,
+    /// [`EcsRamRoleCredentialsProvider`]
+    EcsRamRole,
+}
+
+/// 默认的凭证链，依次尝试以下来源：
+/// 1. 环境变量[`EnvCredentialsProvider`]
+/// 2. OIDC（ACK的RRSA）[`OidcRoleCredentialsProvider`]
+/// 3. 阿里云CLI的配置文件[`CliProfileCredentialsProvider`]
+/// 4. ECS实例RAM角色[`EcsRamRoleCredentialsProvider`]
+///
+/// 第一次成功获取凭证后会记住对应的来源，之后只使用这个来源（临时凭证由对应的provider缓存和刷新），
+/// 所有来源都失败时返回包含每个来源错误信息的错误，并在30秒内直接返回这个错误，不再重新尝试，
+/// 避免不在ECS上运行时每次请求都要等待元数据服务超时
+///
+/// oss、sts、email、translate的client没有设置`credentials_provider`时默认使用`DefaultCredentialsChain`
+pub struct DefaultCredentialsChain {
+    providers: Vec<(CredentialsSource, Box<dyn CredentialsProvider>)>,
+    selected: OnceLock<usize>,
+    // 上次所有来源都失败的时间和错误信息
+    last_failure: Mutex<Option<(Instant, String)>>,
+    failure_ttl: Duration,
+}
+
+impl Default for DefaultCredentialsChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DefaultCredentialsChain {
+    pub fn new() -> Self {
+        Self::from_providers(vec![
+            (
+                CredentialsSource::Env,
+                Box::new(EnvCredentialsProvider::new()),
+            ),
+            (
+                CredentialsSource::Oidc,
+                Box::new(OidcRoleCredentialsProvider::builder().build()),
+            ),
+            (
+                CredentialsSource::CliProfile,
+                Box::new(CliProfileCredentialsProvider::builder().build()),
+            ),
+            (
+                CredentialsSource::EcsRamRole,
+                Box::new(EcsRamRoleCredentialsProvider::builder().build()),
+            ),
+        ])
+    }
+
+    fn from_providers(providers: Vec<(CredentialsSource, Box<dyn CredentialsProvider>)>) -> Self {
+        Self {
+            providers,
+            selected: OnceLock::new(),
+            last_failure: Mutex::new(None),
+            failure_ttl: FAILURE_TTL,
+        }
+    }
+
+    /// 成功获取凭证的来源，还没有成功获取过凭证时为`None`
+    pub fn source(&self) -> Option<CredentialsSource> {
+        self.selected.get().map(|&i| self.providers[i].0)
+    }
+}
+
+#[async_trait::async_trait]
+impl CredentialsProvider for DefaultCredentialsChain {
+    async fn load(
+        &self,
+    ) -> Result<Arc<Credentials>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        if let Some(&i) = self.selected.get() {
+            return self.providers[i].1.load().await;
+        }
+        if let Some((at, msg)) = &*self.last_failure.lock().unwrap()
+            && at.elapsed() < self.failure_ttl
+        {
+            return Err(Box::new(Error::Common(msg.clone())));
+        }
+
+        let mut errors = vec![];
+        for (i, (source, provider)) in self.providers.iter().enumerate() {
+            match provider.load().await {
+                Ok(creds) => {
+                    let _ = self.selected.set(i);
+                    return Ok(creds);
+                }
+                Err(e) => errors.push(format!("{:?}: {}", source, e)),
+            }
+        }
+        let msg = format!(
+            "no credentials found in the default chain: [{}]",
+            errors.join("; ")
+        );
+        *self.last_failure.lock().unwrap() = Some((Instant::now(), msg.clone()));
+        Err(Box::new(Error::Common(msg)))
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn default_chain_test() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    // 记录调用次数，`ok`为false时返回错误
+    struct Provider {
+        ok: bool,
+        calls: Arc<AtomicU32>,
+    }
+    #[async_trait::async_trait]
+    impl CredentialsProvider for Provider {
+        async fn load(
+            &self,
+        ) -> Result<Arc<Credentials>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.ok {
+                Ok(Arc::new(Credentials::new("id", "secret", None, None)))
+            } else {
+                Err(Box::new(Error::Common("not configured".to_owned())))
+            }
+        }
+    }
+
+    let calls: Vec<_> = (0..3).map(|_| Arc::new(AtomicU32::new(0))).collect();
+    let provider = |ok, i: usize| -> Box<dyn CredentialsProvider> {
+        Box::new(Provider {
+            ok,
+            calls: calls[i].clone(),
+        })
+    };
+    let chain = DefaultCredentialsChain::from_providers(vec![
+        (CredentialsSource::Env, provider(false, 0)),
+        (CredentialsSource::CliProfile, provider(true, 1)),
+        (CredentialsSource::EcsRamRole, provider(true, 2)),
+    ]);
+
+    assert_eq!(chain.source(), None);
+    chain.load().await.unwrap();
+    chain.load().await.unwrap();
+    assert_eq!(chain.source(), Some(CredentialsSource::CliProfile));
+    // 记住成功的来源后不再尝试前面的来源
+    let counts: Vec<_> = calls.iter().map(|c| c.load(Ordering::SeqCst)).collect();
+    assert_eq!(counts, [1, 2, 0]);
+
+    calls[0].store(0, Ordering::SeqCst);
+    let mut chain = DefaultCredentialsChain::from_providers(vec![
+        (CredentialsSource::Env, provider(false, 0)),
+        (CredentialsSource::Oidc, provider(false, 0)),
+    ]);
+    let err = chain.load().await.unwrap_err().to_string();
+    assert!(err.contains("Env: error: not configured"));
+    assert!(err.contains("Oidc: error: not configured"));
+    // 所有来源都失败后，一段时间内直接返回上次的错误
+    assert_eq!(chain.load().await.unwrap_err().to_string(), err);
+    assert_eq!(calls[0].load(Ordering::SeqCst), 2);
+    // 过期后重新尝试所有来源
+    chain.failure_ttl = Duration::ZERO;
+    chain.load().await.unwrap_err();
+    assert_eq!(calls[0].load(Ordering::SeqCst), 4);
+}
@@ -0,0 +1,245 @@
+use super::cache::{CredentialsCache, parse_expiration};
+use super::{Credentials, CredentialsProvider};
+use bon::bon;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::{Method, Url};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use time::OffsetDateTime;
+use u_sdk_common::Error;
+use u_sdk_common::helper::into_request_failed_error;
+use u_sdk_common::transport::{HttpTransport, new_request, transport_with_timeouts};
+
+const ENV_ECS_METADATA: &str = "ALIBABA_CLOUD_ECS_METADATA";
+const METADATA_TOKEN: &str = "x-aliyun-ecs-metadata-token";
+const METADATA_TOKEN_TTL: &str = "x-aliyun-ecs-metadata-token-ttl-seconds";
+
+/// 从ECS实例元数据服务获取实例RAM角色临时凭证的[`CredentialsProvider`]
+///
+/// 默认使用加固模式（IMDSv2）：先获取元数据服务的token，再使用token获取凭证，
+/// 元数据服务返回错误时回退到普通模式，可以通过`disable_imds_v1`禁止回退；
+/// 连接失败、超时等网络错误直接返回，不再请求普通模式
+///
+/// 凭证会被缓存，在过期前`refresh_margin`开始刷新
+pub struct EcsRamRoleCredentialsProvider {
+    role_name: Option<String>,
+    endpoint: String,
+    disable_imds_v1: bool,
+    http_client: Arc<dyn HttpTransport>,
+    cache: CredentialsCache,
+}
+
+#[bon]
+impl EcsRamRoleCredentialsProvider {
+    /// - `role_name`：实例RAM角色的名称，默认使用环境变量`ALIBABA_CLOUD_ECS_METADATA`，
+    ///   都没有设置时从元数据服务获取
+    /// - `endpoint`：元数据服务的地址，默认为`http://100.100.100.200`
+    /// - `disable_imds_v1`：获取token失败时是否返回错误，默认为`false`
+    /// - `http_client`：`reqwest::Client`或其它[`HttpTransport`]，默认使用连接超时为1秒、总超时为5秒的`reqwest::Client`
+    /// - `refresh_margin`：在凭证过期前多久开始刷新，默认为5分钟
+    #[builder(on(String, into))]
+    pub fn new(
+        role_name: Option<String>,
+        #[builder(default = "http://100.100.100.200".to_owned())] endpoint: String,
+        #[builder(default)] disable_imds_v1: bool,
+        #[builder(with = |http_client: impl HttpTransport + 'static| Arc::new(http_client))]
+        http_client: Option<Arc<dyn HttpTransport>>,
+        #[builder(default = Duration::from_secs(300))] refresh_margin: Duration,
+    ) -> Self {
+        let role_name = role_name.or_else(|| {
+            std::env::var(ENV_ECS_METADATA)
+                .ok()
+                .filter(|r| !r.is_empty())
+        });
+        let http_client = http_client.unwrap_or_else(|| {
+            transport_with_timeouts(
+                Some(Duration::from_secs(1)),
+                None,
+                Some(Duration::from_secs(5)),
+            )
+        });
+        Self {
+            role_name,
+            endpoint: endpoint.trim_end_matches('/').to_owned(),
+            disable_imds_v1,
+            http_client,
+            cache: CredentialsCache::new(refresh_margin),
+        }
+    }
+
+    fn url(&self, path: &str) -> Result<Url, Error> {
+        let url = format!("{}{}", self.endpoint, path);
+        Url::parse(&url).map_err(|e| Error::Common(format!("invalid url `{}`: {}", url, e)))
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        headers: HeaderMap,
+    ) -> Result<String, Error> {
+        let req = new_request(method, self.url(path)?, headers, None);
+        let resp = self.http_client.execute(req).await?;
+        if !resp.status().is_success() {
+            return Err(into_request_failed_error(resp).await);
+        }
+        Ok(resp.text().await?)
+    }
+
+    async fn metadata_token(&self) -> Result<Option<String>, Error> {
+        let mut headers = HeaderMap::new();
+        headers.insert(METADATA_TOKEN_TTL, HeaderValue::from_static("21600"));
+        match self
+            .request(Method::PUT, "/latest/api/token", headers)
+            .await
+        {
+            Ok(token) => Ok(Some(token)),
+            // 只有元数据服务有响应时才回退，网络不通时回退也会失败
+            Err(Error::Service(_)) if !self.disable_imds_v1 => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn refresh(&self) -> Result<Credentials, Error> {
+        let mut headers = HeaderMap::new();
+        if let Some(token) = self.metadata_token().await? {
+            let mut value = HeaderValue::from_str(&token)
+                .map_err(|e| Error::Common(format!("invalid metadata token: {}", e)))?;
+            value.set_sensitive(true);
+            headers.insert(METADATA_TOKEN, value);
+        }
+
+        const PATH: &str = "/latest/meta-data/ram/security-credentials/";
+        let role_name = match &self.role_name {
+            Some(name) => name.clone(),
+            None => self
+                .request(Method::GET, PATH, headers.clone())
+                .await?
+                .trim()
+                .to_owned(),
+        };
+        let text = self
+            .request(Method::GET, &format!("{}{}", PATH, role_name), headers)
+            .await?;
+
+        let res: EcsCredentials = serde_json::from_str(&text)
+            .map_err(|e| Error::Common(format!("parse ecs credentials failed: {}", e)))?;
+        if res.code != "Success" {
+            return Err(Error::Common(format!(
+                "get ecs credentials failed, code: {}",
+                res.code
+            )));
+        }
+        Ok(Credentials::new(
+            res.access_key_id,
+            res.access_key_secret,
+            Some(res.security_token),
+            Some(parse_expiration(&res.expiration)?),
+        ))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct EcsCredentials {
+    code: String,
+    access_key_id: String,
+    access_key_secret: String,
+    security_token: String,
+    expiration: String,
+}
+
+#[async_trait::async_trait]
+impl CredentialsProvider for EcsRamRoleCredentialsProvider {
+    async fn load(
+        &self,
+    ) -> Result<Arc<Credentials>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        Ok(self
+            .cache
+            .get_or_refresh(OffsetDateTime::now_utc, self.refresh())
+            .await?)
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn ecs_ram_role_test() {
+    use super::mock_server;
+    use time::format_description::well_known::Rfc3339;
+
+    let expiration = (OffsetDateTime::now_utc() + time::Duration::hours(6))
+        .format(&Rfc3339)
+        .unwrap();
+    let creds = format!(
+        r#"{{"AccessKeyId":"STS.id","AccessKeySecret":"secret","Expiration":"{}","SecurityToken":"token","LastUpdated":"","Code":"Success"}}"#,
+        expiration
+    );
+    let (addr, server) = mock_server(vec![
+        (200, "imds-token".to_owned()),
+        (200, "role".to_owned()),
+        (200, creds.clone()),
+    ])
+    .await;
+
+    let provider = EcsRamRoleCredentialsProvider::builder()
+        .endpoint(format!("http://{}", addr))
+        .build();
+    let res = provider.load().await.unwrap();
+    assert_eq!(res.access_key_id, "STS.id");
+    assert_eq!(
+        res.sts_security_token.as_ref().map(|t| t.expose_secret()),
+        Some("token")
+    );
+    // 第二次load使用缓存
+    assert_eq!(provider.load().await.unwrap().access_key_id, "STS.id");
+
+    let requests = server.await.unwrap();
+    assert!(requests[0].starts_with("PUT /latest/api/token "));
+    assert!(requests[0].contains("x-aliyun-ecs-metadata-token-ttl-seconds: 21600"));
+    assert!(requests[1].starts_with("GET /latest/meta-data/ram/security-credentials/ "));
+    assert!(requests[2].starts_with("GET /latest/meta-data/ram/security-credentials/role "));
+    assert!(requests[2].contains("x-aliyun-ecs-metadata-token: imds-token"));
+
+    // 获取token返回错误时回退到普通模式，不携带token
+    let (addr, server) = mock_server(vec![(403, "".to_owned()), (200, creds)]).await;
+    let provider = EcsRamRoleCredentialsProvider::builder()
+        .endpoint(format!("http://{}", addr))
+        .role_name("role")
+        .build();
+    assert_eq!(provider.load().await.unwrap().access_key_id, "STS.id");
+    let requests = server.await.unwrap();
+    assert!(requests[1].starts_with("GET /latest/meta-data/ram/security-credentials/role "));
+    assert!(!requests[1].contains("x-aliyun-ecs-metadata-token:"));
+
+    // 连接失败时直接返回错误，不再回退到普通模式
+    struct CountingTransport(std::sync::atomic::AtomicU32);
+    #[async_trait::async_trait]
+    impl HttpTransport for CountingTransport {
+        async fn execute(&self, request: reqwest::Request) -> Result<reqwest::Response, Error> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(reqwest::Client::new().execute(request).await?)
+        }
+    }
+    let addr = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap()
+    };
+    let transport = Arc::new(CountingTransport(Default::default()));
+    let provider = EcsRamRoleCredentialsProvider::builder()
+        .endpoint(format!("http://{}", addr))
+        .role_name("role")
+        .http_client(Arc::clone(&transport))
+        .build();
+    assert!(provider.load().await.is_err());
+    assert_eq!(transport.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    // 禁止回退到普通模式时，获取token失败返回错误
+    let (addr, _server) = mock_server(vec![(403, "".to_owned())]).await;
+    let provider = EcsRamRoleCredentialsProvider::builder()
+        .endpoint(format!("http://{}", addr))
+        .role_name("role")
+        .disable_imds_v1(true)
+        .build();
+    assert!(provider.load().await.is_err());
+}
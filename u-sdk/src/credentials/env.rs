@@ -0,0 +1,71 @@
+use super::{Credentials, CredentialsProvider};
+use std::sync::Arc;
+use u_sdk_common::Error;
+
+pub(crate) const ENV_ACCESS_KEY_ID: &str = "ALIBABA_CLOUD_ACCESS_KEY_ID";
+pub(crate) const ENV_ACCESS_KEY_SECRET: &str = "ALIBABA_CLOUD_ACCESS_KEY_SECRET";
+pub(crate) const ENV_SECURITY_TOKEN: &str = "ALIBABA_CLOUD_SECURITY_TOKEN";
+
+/// 从环境变量读取凭证的[`CredentialsProvider`]
+///
+/// - `ALIBABA_CLOUD_ACCESS_KEY_ID`：必须
+/// - `ALIBABA_CLOUD_ACCESS_KEY_SECRET`：必须
+/// - `ALIBABA_CLOUD_SECURITY_TOKEN`：使用STS临时凭证时设置
+///
+/// 每次`load`都会重新读取环境变量
+#[derive(Debug, Clone, Default)]
+pub struct EnvCredentialsProvider;
+
+impl EnvCredentialsProvider {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 读取环境变量中的凭证，`ALIBABA_CLOUD_ACCESS_KEY_ID`或`ALIBABA_CLOUD_ACCESS_KEY_SECRET`
+    /// 没有设置或为空时返回错误
+    pub fn credentials(&self) -> Result<Credentials, Error> {
+        credentials_from(|name| std::env::var(name).ok())
+    }
+}
+
+fn credentials_from(var: impl Fn(&str) -> Option<String>) -> Result<Credentials, Error> {
+    let var = |name: &str| var(name).filter(|v| !v.is_empty());
+    let required = |name: &str| {
+        var(name).ok_or_else(|| Error::Common(format!("environment variable {} is not set", name)))
+    };
+
+    Ok(Credentials::new(
+        required(ENV_ACCESS_KEY_ID)?,
+        required(ENV_ACCESS_KEY_SECRET)?,
+        var(ENV_SECURITY_TOKEN),
+        None,
+    ))
+}
+
+#[async_trait::async_trait]
+impl CredentialsProvider for EnvCredentialsProvider {
+    async fn load(
+        &self,
+    ) -> Result<Arc<Credentials>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        Ok(Arc::new(self.credentials()?))
+    }
+}
+
+#[test]
+fn env_credentials_test() {
+    use std::collections::HashMap;
+
+    let vars = HashMap::from([
+        (ENV_ACCESS_KEY_ID, "id"),
+        (ENV_ACCESS_KEY_SECRET, "secret"),
+        (ENV_SECURITY_TOKEN, ""),
+    ]);
+    let creds = credentials_from(|name| vars.get(name).map(|v| v.to_string())).unwrap();
+    assert_eq!(creds.access_key_id, "id");
+    assert_eq!(creds.access_key_secret.expose_secret(), "secret");
+    // 空的token视为没有设置
+    assert_eq!(creds.sts_security_token, None);
+
+    let vars = HashMap::from([(ENV_ACCESS_KEY_ID, "id")]);
+    assert!(credentials_from(|name| vars.get(name).map(|v| v.to_string())).is_err());
+}
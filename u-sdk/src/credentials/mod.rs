@@ -0,0 +1,134 @@
+//! Credentials and CredentialsProvider definitions.
+//!
+//! 在构建client的时候需要传入实现了CredentialsProvider trait的类型，因为trait `CredentialsProvider`的load方法是异步的，
+//! 所以有些像预签名等api本身是同步的，但是需要用到Credentials的时候，可能需要异步获取Credentials，所以这类操作因此也变为异步的。
+//!
+//! 内置的CredentialsProvider：
+//! - [`StaticCredentialsProvider`](crate::credentials::StaticCredentialsProvider)：固定的凭证
+//! - [`EnvCredentialsProvider`](crate::credentials::EnvCredentialsProvider)：从`ALIBABA_CLOUD_ACCESS_KEY_ID`等环境变量读取
+//! - [`CliProfileCredentialsProvider`](crate::credentials::CliProfileCredentialsProvider)：读取阿里云CLI的配置文件`~/.aliyun/config.json`
+//! - [`EcsRamRoleCredentialsProvider`](crate::credentials::EcsRamRoleCredentialsProvider)：从ECS实例元数据服务获取实例RAM角色的临时凭证
+//! - [`OidcRoleCredentialsProvider`](crate::credentials::OidcRoleCredentialsProvider)：使用OIDC token获取临时凭证，用于ACK的RRSA
+//! - [`DefaultCredentialsChain`](crate::credentials::DefaultCredentialsChain)：依次尝试环境变量、OIDC、CLI配置文件和ECS实例RAM角色，
+//!   client没有设置`credentials_provider`时默认使用
+//!
+//! `Credentials`中的`access_key_secret`和`sts_security_token`使用[`Secret`](crate::credentials::Secret)保存，
+//! `Debug`输出时显示为`***`，需要原始值时调用`expose_secret`。
+//!
+//! 使用STS临时凭证时，可以使用自动刷新的`sts::AssumeRoleCredentialsProvider`（需要开启`sts` feature）。
+//!
+//! # Example
+//!
+//! ```no_run
+//! use serde::Deserialize;
+//! use std::sync::Arc;
+//! use u_sdk::credentials::{Credentials, CredentialsProvider};
+//! use u_sdk::oss;
+//!
+//! #[derive(Deserialize, Debug)]
+//! pub struct OssConfig {
+//!     pub access_key_id: String,
+//!     pub access_key_secret: String,
+//!     pub endpoint: String,
+//!     pub bucket_name: String,
+//!     pub region: String,
+//! }
+//!
+//! pub struct OssCredsProvider {
+//!     creds: Arc<Credentials>,
+//! }
+//!
+//! impl OssCredsProvider {
+//!     pub fn new(access_key_id: String, access_key_secret: String) -> Self {
+//!         Self {
+//!             creds: Credentials::new(access_key_id, access_key_secret, None, None).into(),
+//!         }
+//!     }
+//! }
+//!
+//! #[async_trait::async_trait]
+//! impl CredentialsProvider for OssCredsProvider {
+//!     async fn load(
+//!         &self,
+//!     ) -> Result<Arc<Credentials>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+//!         Ok(Arc::clone(&self.creds))
+//!     }
+//! }
+//!
+//! fn get_oss_client() -> oss::Client {
+//!     let file_str = std::fs::read_to_string("tests/oss/config.toml").unwrap();
+//!     let conf = toml::from_str::<OssConfig>(&file_str).unwrap();
+//!     let creds_provider = Arc::new(OssCredsProvider::new(
+//!         conf.access_key_id,
+//!         conf.access_key_secret,
+//!     ));
+//!     oss::Client::builder()
+//!         .credentials_provider(creds_provider)
+//!         .endpoint(conf.endpoint)
+//!         .region(conf.region)
+//!         .bucket(conf.bucket_name)
+//!         .build()
+//! }
+//! ```
+
+pub(crate) mod cache;
+mod chain;
+mod ecs;
+mod env;
+mod oidc;
+mod profile;
+
+pub use chain::{CredentialsSource, DefaultCredentialsChain};
+pub use ecs::EcsRamRoleCredentialsProvider;
+pub use env::EnvCredentialsProvider;
+pub use oidc::OidcRoleCredentialsProvider;
+pub use profile::CliProfileCredentialsProvider;
+pub use u_sdk_common::credentials::{Credentials, CredentialsProvider, StaticCredentialsProvider};
+pub use u_sdk_common::secret::Secret;
+
+// 本地的http服务，依次返回`responses`中的状态码和body，结束后返回收到的所有请求
+#[cfg(test)]
+async fn mock_server(
+    responses: Vec<(u16, String)>,
+) -> (std::net::SocketAddr, tokio::task::JoinHandle<Vec<String>>) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        let mut requests = vec![];
+        for (status, body) in responses {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = vec![];
+            // 读取完整的请求头和body
+            loop {
+                let mut chunk = [0; 4096];
+                let n = stream.read(&mut chunk).await.unwrap();
+                buf.extend_from_slice(&chunk[..n]);
+                let req = String::from_utf8_lossy(&buf);
+                if let Some(pos) = req.find("\r\n\r\n") {
+                    let content_length = req[..pos]
+                        .lines()
+                        .find_map(|l| l.strip_prefix("content-length: "))
+                        .map_or(0, |l| l.parse::<usize>().unwrap());
+                    if buf.len() >= pos + 4 + content_length {
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+            requests.push(String::from_utf8_lossy(&buf).into_owned());
+            let resp = format!(
+                "HTTP/1.1 {} OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.write_all(resp.as_bytes()).await.unwrap();
+        }
+        requests
+    });
+    (addr, server)
+}
@@ -0,0 +1,242 @@
+use super::cache::{CredentialsCache, parse_expiration};
+use super::{Credentials, CredentialsProvider};
+use bon::bon;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderValue};
+use reqwest::{Method, Url};
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use time::OffsetDateTime;
+use u_sdk_common::Error;
+use u_sdk_common::helper::{now_iso8601, parse_json_response};
+use u_sdk_common::transport::{HttpTransport, default_transport, new_request};
+
+pub(crate) const ENV_ROLE_ARN: &str = "ALIBABA_CLOUD_ROLE_ARN";
+pub(crate) const ENV_OIDC_PROVIDER_ARN: &str = "ALIBABA_CLOUD_OIDC_PROVIDER_ARN";
+pub(crate) const ENV_OIDC_TOKEN_FILE: &str = "ALIBABA_CLOUD_OIDC_TOKEN_FILE";
+const ENV_ROLE_SESSION_NAME: &str = "ALIBABA_CLOUD_ROLE_SESSION_NAME";
+const ENV_STS_REGION: &str = "ALIBABA_CLOUD_STS_REGION";
+const ENV_VPC_ENDPOINT_ENABLED: &str = "ALIBABA_CLOUD_VPC_ENDPOINT_ENABLED";
+
+/// 使用OIDC token调用`AssumeRoleWithOIDC`获取临时凭证的[`CredentialsProvider`]，
+/// 用于ACK集群的RRSA（RAM Roles for Service Accounts）
+///
+/// 没有在builder中设置的参数从以下环境变量读取，ACK开启RRSA后会自动注入这些环境变量：
+/// - `ALIBABA_CLOUD_ROLE_ARN`：扮演的RAM角色
+/// - `ALIBABA_CLOUD_OIDC_PROVIDER_ARN`：OIDC身份提供商
+/// - `ALIBABA_CLOUD_OIDC_TOKEN_FILE`：OIDC token文件的路径，每次刷新都会重新读取
+/// - `ALIBABA_CLOUD_ROLE_SESSION_NAME`：可选
+///
+/// 凭证会被缓存，在过期前`refresh_margin`开始刷新
+pub struct OidcRoleCredentialsProvider {
+    role_arn: Option<String>,
+    oidc_provider_arn: Option<String>,
+    oidc_token_file: Option<PathBuf>,
+    role_session_name: String,
+    duration_seconds: Option<u32>,
+    policy: Option<String>,
+    sts_endpoint: String,
+    http_client: Arc<dyn HttpTransport>,
+    cache: CredentialsCache,
+}
+
+#[bon]
+impl OidcRoleCredentialsProvider {
+    /// - `role_session_name`：默认为`u-sdk-{时间戳}`
+    /// - `duration_seconds`：凭证的有效期，默认为3600秒
+    /// - `policy`：json格式的权限策略，用于进一步限制凭证的权限
+    /// - `sts_endpoint`：STS的endpoint，默认为`sts.aliyuncs.com`，
+    ///   设置了环境变量`ALIBABA_CLOUD_STS_REGION`时为`sts.{region}.aliyuncs.com`，
+    ///   同时`ALIBABA_CLOUD_VPC_ENDPOINT_ENABLED`为`true`时使用VPC endpoint。不包含scheme时使用https
+    /// - `http_client`：`reqwest::Client`或其它[`HttpTransport`]，默认为`reqwest::Client::new()`
+    /// - `refresh_margin`：在凭证过期前多久开始刷新，默认为5分钟
+    #[builder(on(String, into))]
+    pub fn new(
+        role_arn: Option<String>,
+        oidc_provider_arn: Option<String>,
+        #[builder(into)] oidc_token_file: Option<PathBuf>,
+        role_session_name: Option<String>,
+        duration_seconds: Option<u32>,
+        policy: Option<String>,
+        sts_endpoint: Option<String>,
+        #[builder(
+            default = default_transport(),
+            with = |http_client: impl HttpTransport + 'static| Arc::new(http_client)
+        )]
+        http_client: Arc<dyn HttpTransport>,
+        #[builder(default = Duration::from_secs(300))] refresh_margin: Duration,
+    ) -> Self {
+        let role_session_name = role_session_name
+            .or_else(|| env_var(ENV_ROLE_SESSION_NAME))
+            .unwrap_or_else(|| format!("u-sdk-{}", OffsetDateTime::now_utc().unix_timestamp()));
+        let sts_endpoint = sts_endpoint.unwrap_or_else(|| match env_var(ENV_STS_REGION) {
+            Some(region) if env_var(ENV_VPC_ENDPOINT_ENABLED).as_deref() == Some("true") => {
+                format!("sts-vpc.{}.aliyuncs.com", region)
+            }
+            Some(region) => format!("sts.{}.aliyuncs.com", region),
+            None => "sts.aliyuncs.com".to_owned(),
+        });
+
+        Self {
+            role_arn: role_arn.or_else(|| env_var(ENV_ROLE_ARN)),
+            oidc_provider_arn: oidc_provider_arn.or_else(|| env_var(ENV_OIDC_PROVIDER_ARN)),
+            oidc_token_file: oidc_token_file
+                .or_else(|| env_var(ENV_OIDC_TOKEN_FILE).map(Into::into)),
+            role_session_name,
+            duration_seconds,
+            policy,
+            sts_endpoint,
+            http_client,
+            cache: CredentialsCache::new(refresh_margin),
+        }
+    }
+
+    async fn refresh(&self) -> Result<Credentials, Error> {
+        let missing =
+            |name: &str| Error::Common(format!("environment variable {} is not set", name));
+        let role_arn = self
+            .role_arn
+            .as_deref()
+            .ok_or_else(|| missing(ENV_ROLE_ARN))?;
+        let provider_arn = self
+            .oidc_provider_arn
+            .as_deref()
+            .ok_or_else(|| missing(ENV_OIDC_PROVIDER_ARN))?;
+        let token_file = self
+            .oidc_token_file
+            .as_ref()
+            .ok_or_else(|| missing(ENV_OIDC_TOKEN_FILE))?;
+        let token = tokio::fs::read_to_string(token_file).await.map_err(|e| {
+            Error::Common(format!(
+                "read oidc token file {} failed: {}",
+                token_file.display(),
+                e
+            ))
+        })?;
+
+        let duration_seconds = self.duration_seconds.map(|d| d.to_string());
+        let timestamp = now_iso8601();
+        let mut params = vec![
+            ("Action", "AssumeRoleWithOIDC"),
+            ("Format", "JSON"),
+            ("Version", "2015-04-01"),
+            ("Timestamp", timestamp.as_str()),
+            ("RoleArn", role_arn),
+            ("OIDCProviderArn", provider_arn),
+            ("OIDCToken", token.trim()),
+            ("RoleSessionName", &self.role_session_name),
+        ];
+        if let Some(d) = &duration_seconds {
+            params.push(("DurationSeconds", d));
+        }
+        if let Some(policy) = &self.policy {
+            params.push(("Policy", policy));
+        }
+        let body = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(params)
+            .finish();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        );
+        let req = new_request(Method::POST, self.url()?, headers, Some(body.into()));
+        let resp = self.http_client.execute(req).await?;
+        let res: OidcResponse = parse_json_response(resp).await?;
+
+        let creds = res.credentials;
+        Ok(Credentials::new(
+            creds.access_key_id,
+            creds.access_key_secret,
+            Some(creds.security_token),
+            Some(parse_expiration(&creds.expiration)?),
+        ))
+    }
+
+    fn url(&self) -> Result<Url, Error> {
+        let url = if self.sts_endpoint.contains("://") {
+            self.sts_endpoint.clone()
+        } else {
+            format!("https://{}", self.sts_endpoint)
+        };
+        Url::parse(&url).map_err(|e| Error::Common(format!("invalid url `{}`: {}", url, e)))
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct OidcResponse {
+    credentials: OidcCredentials,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct OidcCredentials {
+    access_key_id: String,
+    access_key_secret: String,
+    security_token: String,
+    expiration: String,
+}
+
+#[async_trait::async_trait]
+impl CredentialsProvider for OidcRoleCredentialsProvider {
+    async fn load(
+        &self,
+    ) -> Result<Arc<Credentials>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        Ok(self
+            .cache
+            .get_or_refresh(OffsetDateTime::now_utc, self.refresh())
+            .await?)
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn oidc_role_test() {
+    use super::mock_server;
+    use time::format_description::well_known::Rfc3339;
+
+    let token_file = std::env::temp_dir().join(format!("u-sdk-oidc-{}", std::process::id()));
+    std::fs::write(&token_file, "oidc-token\n").unwrap();
+
+    let expiration = (OffsetDateTime::now_utc() + time::Duration::hours(1))
+        .format(&Rfc3339)
+        .unwrap();
+    let body = format!(
+        r#"{{"RequestId":"1","Credentials":{{"AccessKeyId":"STS.id","AccessKeySecret":"secret","SecurityToken":"token","Expiration":"{}"}}}}"#,
+        expiration
+    );
+    let (addr, server) = mock_server(vec![(200, body)]).await;
+
+    let provider = OidcRoleCredentialsProvider::builder()
+        .role_arn("acs:ram::123:role/test")
+        .oidc_provider_arn("acs:ram::123:oidc-provider/ack")
+        .oidc_token_file(&token_file)
+        .role_session_name("s")
+        .sts_endpoint(format!("http://{}", addr))
+        .build();
+    let res = provider.load().await.unwrap();
+    assert_eq!(res.access_key_id, "STS.id");
+    assert_eq!(
+        res.sts_security_token.as_ref().map(|t| t.expose_secret()),
+        Some("token")
+    );
+    assert_eq!(provider.load().await.unwrap().access_key_id, "STS.id");
+
+    let requests = server.await.unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].starts_with("POST / "));
+    assert!(requests[0].contains("Action=AssumeRoleWithOIDC"));
+    assert!(requests[0].contains("OIDCToken=oidc-token&"));
+    assert!(requests[0].contains("RoleArn=acs%3Aram%3A%3A123%3Arole%2Ftest"));
+    // 匿名调用，不需要签名
+    assert!(!requests[0].contains("authorization"));
+
+    std::fs::remove_file(&token_file).unwrap();
+}
@@ -0,0 +1,217 @@
+use super::{Credentials, CredentialsProvider, Secret, StaticCredentialsProvider};
+use bon::bon;
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use u_sdk_common::Error;
+
+const ENV_PROFILE: &str = "ALIBABA_CLOUD_PROFILE";
+
+/// 读取阿里云CLI配置文件`~/.aliyun/config.json`的[`CredentialsProvider`]
+///
+/// 支持以下的凭证模式：
+/// - `AK`：使用`access_key_id`、`access_key_secret`
+/// - `StsToken`：使用`access_key_id`、`access_key_secret`、`sts_token`
+/// - `RamRoleArn`：使用`access_key_id`、`access_key_secret`调用AssumeRole扮演`ram_role_arn`，
+///   临时凭证过期前自动刷新，需要开启`sts` feature
+///
+/// 配置文件在第一次`load`时读取，之后不会重新读取
+pub struct CliProfileCredentialsProvider {
+    path: Option<PathBuf>,
+    profile: Option<String>,
+    inner: OnceLock<Arc<dyn CredentialsProvider>>,
+}
+
+#[bon]
+impl CliProfileCredentialsProvider {
+    /// - `path`：配置文件的路径，默认为`~/.aliyun/config.json`
+    /// - `profile`：使用的profile，默认依次使用环境变量`ALIBABA_CLOUD_PROFILE`、
+    ///   配置文件中的`current`，都没有设置时为`default`
+    #[builder(on(String, into))]
+    pub fn new(#[builder(into)] path: Option<PathBuf>, profile: Option<String>) -> Self {
+        Self {
+            path,
+            profile,
+            inner: OnceLock::new(),
+        }
+    }
+
+    fn inner(&self) -> Result<&Arc<dyn CredentialsProvider>, Error> {
+        if let Some(inner) = self.inner.get() {
+            return Ok(inner);
+        }
+
+        let path = match &self.path {
+            Some(path) => path.clone(),
+            None => default_config_path()?,
+        };
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            Error::Common(format!("read config file {} failed: {}", path.display(), e))
+        })?;
+        let profile = self
+            .profile
+            .clone()
+            .or_else(|| std::env::var(ENV_PROFILE).ok().filter(|p| !p.is_empty()));
+        let inner = provider_from_config(&content, profile.as_deref())?;
+        Ok(self.inner.get_or_init(|| inner))
+    }
+}
+
+#[async_trait::async_trait]
+impl CredentialsProvider for CliProfileCredentialsProvider {
+    async fn load(
+        &self,
+    ) -> Result<Arc<Credentials>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        self.inner()?.load().await
+    }
+}
+
+fn default_config_path() -> Result<PathBuf, Error> {
+    let home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .ok_or_else(|| Error::Common("can not find home directory".to_owned()))?;
+    Ok(PathBuf::from(home).join(".aliyun").join("config.json"))
+}
+
+#[derive(Deserialize)]
+struct Config {
+    #[serde(default)]
+    current: String,
+    #[serde(default)]
+    profiles: Vec<Profile>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Profile {
+    name: String,
+    mode: String,
+    access_key_id: String,
+    access_key_secret: Secret,
+    sts_token: Secret,
+    ram_role_arn: String,
+    ram_session_name: String,
+    expired_seconds: u32,
+    region_id: String,
+}
+
+fn provider_from_config(
+    content: &str,
+    profile: Option<&str>,
+) -> Result<Arc<dyn CredentialsProvider>, Error> {
+    let config: Config = serde_json::from_str(content)
+        .map_err(|e| Error::Common(format!("parse config file failed: {}", e)))?;
+    let name = match profile {
+        Some(name) => name,
+        None if !config.current.is_empty() => &config.current,
+        None => "default",
+    };
+    let profile = config
+        .profiles
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| Error::Common(format!("profile `{}` not found", name)))?;
+
+    let static_provider = |token: Option<Secret>| -> Arc<dyn CredentialsProvider> {
+        Arc::new(StaticCredentialsProvider::new(Credentials {
+            access_key_id: profile.access_key_id.clone(),
+            access_key_secret: profile.access_key_secret.clone(),
+            sts_security_token: token,
+            expires_at: None,
+        }))
+    };
+    match profile.mode.as_str() {
+        "AK" => Ok(static_provider(None)),
+        "StsToken" => {
+            let token = &profile.sts_token;
+            Ok(static_provider(
+                (!token.expose_secret().is_empty()).then(|| token.clone()),
+            ))
+        }
+        "RamRoleArn" => ram_role_arn_provider(static_provider(None), profile),
+        mode => Err(Error::Common(format!(
+            "unsupported mode `{}` of profile `{}`",
+            mode, name
+        ))),
+    }
+}
+
+#[cfg(feature = "sts")]
+fn ram_role_arn_provider(
+    source: Arc<dyn CredentialsProvider>,
+    profile: Profile,
+) -> Result<Arc<dyn CredentialsProvider>, Error> {
+    use crate::sts;
+
+    let host = match profile.region_id.as_str() {
+        "" => "sts.aliyuncs.com".to_owned(),
+        region => format!("sts.{}.aliyuncs.com", region),
+    };
+    let client = sts::Client::builder()
+        .credentials_provider(source)
+        .host(host)
+        .build();
+    let session_name = match profile.ram_session_name.as_str() {
+        "" => "u-sdk".to_owned(),
+        name => name.to_owned(),
+    };
+    let provider = sts::AssumeRoleCredentialsProvider::builder()
+        .client(client)
+        .role_arn(profile.ram_role_arn)
+        .role_session_name(session_name)
+        .maybe_duration_seconds((profile.expired_seconds > 0).then_some(profile.expired_seconds))
+        .build();
+    Ok(Arc::new(provider))
+}
+
+#[cfg(not(feature = "sts"))]
+fn ram_role_arn_provider(
+    _source: Arc<dyn CredentialsProvider>,
+    profile: Profile,
+) -> Result<Arc<dyn CredentialsProvider>, Error> {
+    Err(Error::Common(format!(
+        "mode `RamRoleArn` of profile `{}` requires the `sts` feature",
+        profile.name
+    )))
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn cli_profile_test() {
+    let config = r#"{
+        "current": "sts",
+        "profiles": [
+            {"name": "default", "mode": "AK", "access_key_id": "id", "access_key_secret": "secret"},
+            {"name": "sts", "mode": "StsToken", "access_key_id": "STS.id", "access_key_secret": "secret", "sts_token": "token"},
+            {"name": "role", "mode": "RamRoleArn", "access_key_id": "id", "access_key_secret": "secret",
+             "ram_role_arn": "acs:ram::123:role/test", "ram_session_name": "s", "expired_seconds": 900, "region_id": "cn-hangzhou"},
+            {"name": "sso", "mode": "CloudSSO"}
+        ]
+    }"#;
+
+    let load = async |profile: Option<&str>| {
+        let provider = provider_from_config(config, profile)?;
+        provider
+            .load()
+            .await
+            .map_err(|e| Error::Common(e.to_string()))
+    };
+
+    let creds = load(Some("default")).await.unwrap();
+    assert_eq!(creds.access_key_id, "id");
+    assert_eq!(creds.sts_security_token, None);
+    // 没有指定profile时使用`current`
+    let creds = load(None).await.unwrap();
+    assert_eq!(creds.access_key_id, "STS.id");
+    assert_eq!(
+        creds.sts_security_token.as_ref().map(|t| t.expose_secret()),
+        Some("token")
+    );
+
+    assert_eq!(
+        provider_from_config(config, Some("role")).is_ok(),
+        cfg!(feature = "sts")
+    );
+    assert!(provider_from_config(config, Some("sso")).is_err());
+    assert!(provider_from_config(config, Some("none")).is_err());
+}
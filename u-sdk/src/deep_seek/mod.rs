@@ -3,8 +3,7 @@
 mod types;
 pub use types::*;
 
-mod error;
-pub use error::Error;
+pub use u_sdk_common::Error;
 
 mod utils;
 
@@ -12,32 +11,86 @@ use async_stream::try_stream;
 use bon::bon;
 use bytes::{Buf, BytesMut};
 use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
+use reqwest::{Method, Request, Response, Url};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio_stream::{Stream, StreamExt};
 use u_sdk_common::helper::{into_request_failed_error, parse_json_response};
+use u_sdk_common::retry::{RetryPolicy, send_with_retry};
+use u_sdk_common::secret::Secret;
+use u_sdk_common::timeout::{idle_timeout, with_timeout};
+use u_sdk_common::transport::{
+    HttpTransport, new_json_request, new_request, transport_with_timeouts,
+};
 use utils::check_msg_list;
 
 const BASE_URL: &str = "https://api.deepseek.com";
 
+fn api_url(path: &str) -> Url {
+    Url::parse(&format!("{}{}", BASE_URL, path)).unwrap()
+}
+
 //region client
 pub struct Client {
-    http_client: reqwest::Client,
+    http_client: Arc<dyn HttpTransport>,
+    auth_val: HeaderValue,
+    retry_policy: RetryPolicy,
+    read_timeout: Option<Duration>,
 }
 
 #[bon]
 impl Client {
+    /// - `api_key`：`String`、`&str`或[`Secret`]，`Debug`输出时显示为`***`
+    /// - `http_client`：`reqwest::Client`或其它[`HttpTransport`]，默认为`reqwest::Client::new()`
+    /// - `retry_policy`：重试策略，默认为[`RetryPolicy::default`]，对话请求只在服务端没有处理请求时（连接失败、限流）重试
+    /// - `connect_timeout`、`read_timeout`、`timeout`：连接、读取和单次请求的总超时，
+    ///   只在没有指定`http_client`时生效，默认不限制。`read_timeout`同时作为
+    ///   `chat_by_stream`的默认空闲超时，对传入的`http_client`也生效
     #[builder(on(String, into))]
-    pub fn new(api_key: String) -> Self {
-        let mut header_map = HeaderMap::new();
-        let mut auth_val = HeaderValue::from_str(&format!("Bearer {}", api_key)).unwrap();
+    pub fn new(
+        #[builder(into)] api_key: Secret,
+        #[builder(with = |http_client: impl HttpTransport + 'static| Arc::new(http_client))]
+        http_client: Option<Arc<dyn HttpTransport>>,
+        #[builder(default)] retry_policy: RetryPolicy,
+        connect_timeout: Option<Duration>,
+        read_timeout: Option<Duration>,
+        timeout: Option<Duration>,
+    ) -> Self {
+        let http_client = http_client
+            .unwrap_or_else(|| transport_with_timeouts(connect_timeout, read_timeout, timeout));
+        let bearer = Secret::new(format!("Bearer {}", api_key.expose_secret()));
+        let mut auth_val = HeaderValue::from_str(bearer.expose_secret()).unwrap();
         auth_val.set_sensitive(true);
-        header_map.insert(AUTHORIZATION, auth_val);
 
-        let http_client = reqwest::Client::builder()
-            .default_headers(header_map)
-            .build()
-            .unwrap();
+        Self {
+            http_client,
+            auth_val,
+            retry_policy,
+            read_timeout,
+        }
+    }
+
+    async fn send<F>(
+        &self,
+        idempotent: bool,
+        make_request: impl FnMut() -> F,
+    ) -> Result<Response, Error>
+    where
+        F: Future<Output = Result<Request, Error>>,
+    {
+        send_with_retry(
+            &self.retry_policy,
+            self.http_client.as_ref(),
+            idempotent,
+            make_request,
+        )
+        .await
+    }
 
-        Self { http_client }
+    fn auth_header(&self) -> HeaderMap {
+        let mut header_map = HeaderMap::new();
+        header_map.insert(AUTHORIZATION, self.auth_val.clone());
+        header_map
     }
 
     pub fn chat_builder(&self) -> ChatBuilder<'_> {
@@ -46,9 +99,14 @@ impl Client {
 
     pub async fn check_balance(&self) -> Result<CheckBalanceResponse, Error> {
         let resp = self
-            .http_client
-            .get(format!("{}/user/balance", BASE_URL))
-            .send()
+            .send(true, move || async move {
+                Ok(new_request(
+                    Method::GET,
+                    api_url("/user/balance"),
+                    self.auth_header(),
+                    None,
+                ))
+            })
             .await?;
 
         let res = parse_json_response(resp).await?;
@@ -81,16 +139,26 @@ impl Chat<'_> {
             ));
         }
 
-        let client = self.client;
-        let resp = client
-            .http_client
-            .post(format!("{}/chat/completions", BASE_URL))
-            .json(self)
-            .send()
-            .await?;
+        with_timeout(self.timeout, async {
+            let resp = self.send_chat().await?;
+            let res = parse_json_response(resp).await?;
+            Ok(res)
+        })
+        .await
+    }
 
-        let res = parse_json_response(resp).await?;
-        Ok(res)
+    async fn send_chat(&self) -> Result<Response, Error> {
+        let client = self.client;
+        client
+            .send(false, move || async move {
+                new_json_request(
+                    Method::POST,
+                    api_url("/chat/completions"),
+                    client.auth_header(),
+                    self,
+                )
+            })
+            .await
     }
 
     pub async fn chat_by_stream(
@@ -104,25 +172,24 @@ impl Chat<'_> {
             ));
         }
 
-        let resp = self
-            .client
-            .http_client
-            .post(format!("{}/chat/completions", BASE_URL))
-            .json(self)
-            .send()
-            .await?;
+        let resp = with_timeout(self.timeout, self.send_chat()).await?;
 
         if !resp.status().is_success() {
-            return Err(into_request_failed_error(resp).await.into());
+            return Err(into_request_failed_error(resp).await);
         }
 
-        let mut byte_stream = resp.bytes_stream();
+        let idle = self.idle_timeout.or(self.client.read_timeout);
+        let mut byte_stream = idle_timeout(
+            resp.bytes_stream()
+                .map(|chunk| chunk.map_err(Error::Reqwest)),
+            idle,
+        );
 
         let event_stream = try_stream! {
             let mut buffer = BytesMut::with_capacity(4096);
 
             while let Some(chunk) = byte_stream.next().await {
-                // 如果底层网络错误，会通过 `?` 返回 Err(Error) 并终止流
+                // 如果底层网络错误或空闲超时，会通过 `?` 返回 Err(Error) 并终止流
                 let chunk = chunk?;
                 buffer.extend(chunk);
 
@@ -163,3 +230,21 @@ fn parse_event_block(s: &str) -> Result<Option<StreamEventData>, Error> {
     }
 }
 //endregion
+
+#[test]
+fn send_future_is_send_test() {
+    // 对话请求需要能够在`tokio::spawn`中使用
+    fn assert_send<T: Send>(_: T) {}
+    let client = Client::builder().api_key("key").build();
+    let messages = vec![Message {
+        content: "Hi".to_owned(),
+        role: Role::User,
+    }];
+    let chat = client
+        .chat_builder()
+        .messages(&messages)
+        .model("deepseek-chat")
+        .build();
+    assert_send(chat.chat());
+    assert_send(chat.chat_by_stream());
+}
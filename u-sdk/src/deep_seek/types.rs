@@ -1,6 +1,7 @@
 use crate::deep_seek::Client;
 use bon::Builder;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 //region chat body
 #[derive(Builder, Serialize)]
@@ -27,6 +28,12 @@ pub struct Chat<'a> {
     // pub(crate) tool_choice: Option<()>,
     logprobs: Option<bool>,
     top_logprobs: Option<u32>, // Possible values: >= 0 and <= 20 指定此参数时，logprobs 必须为 true。
+    /// `chat`的总超时，包括重试和读取响应的时间，对`chat_by_stream`只包括获取响应头的时间
+    #[serde(skip_serializing)]
+    pub(crate) timeout: Option<Duration>,
+    /// `chat_by_stream`返回的流两次收到数据之间的最大间隔，不设置时使用client的`read_timeout`
+    #[serde(skip_serializing)]
+    pub(crate) idle_timeout: Option<Duration>,
 }
 
 #[derive(Serialize, Debug)]
@@ -1,9 +1,7 @@
-use super::utils::parse_json_response;
 use super::{Client, Error, QueryDomainByParamResult};
 use bon::Builder;
+use reqwest::Method;
 use serde::Serialize;
-use u_sdk_common::helper::into_header_map;
-use u_sdk_common::open_api_sign::{SignParams, get_openapi_request_header};
 
 #[serde_with::skip_serializing_none]
 #[derive(Builder, Serialize)]
@@ -22,30 +20,11 @@ pub struct QueryDomainByParam<'a> {
 
 impl QueryDomainByParam<'_> {
     pub async fn send(&self) -> Result<QueryDomainByParamResult, Error> {
-        let creds = self.client.credentials_provider.load().await?;
-        let sign_params = SignParams {
-            req_method: "GET",
-            host: &self.client.host,
-            query_map: self,
-            x_acs_action: "QueryDomainByParam",
-            x_acs_version: "2015-11-23",
-            x_acs_security_token: creds.sts_security_token.as_deref(),
-            request_body: None,
-            style: &self.client.style,
-        };
-        let (headers, url_) =
-            get_openapi_request_header(&creds.access_key_secret, &creds.access_key_id, sign_params)
-                .map_err(|e| Error::Common(format!("get_common_headers error: {}", e)))?;
-
         let resp = self
             .client
-            .http_client
-            .get(url_)
-            .headers(into_header_map(headers))
-            .send()
+            .api
+            .call("QueryDomainByParam", "2015-11-23", Method::GET, self, None)
             .await?;
-
-        let resp = parse_json_response(resp).await?;
         Ok(resp)
     }
 }
@@ -1,10 +1,8 @@
 use super::Client;
 use super::Error;
-use super::utils::parse_json_response;
 use bon::Builder;
+use reqwest::Method;
 use serde::{Deserialize, Serialize};
-use u_sdk_common::helper::into_header_map;
-use u_sdk_common::open_api_sign::{SignParams, get_openapi_request_header};
 
 //region response
 #[derive(Deserialize, Debug)]
@@ -52,33 +50,19 @@ impl SingleSendEmail<'_> {
             ));
         }
 
-        let client = self.client;
-        let creds = client.credentials_provider.load().await?;
-
-        let sign_params = SignParams {
-            req_method: "GET",
-            host: &client.host,
-            query_map: self,
-            x_acs_action: "SingleSendMail",
-            x_acs_version: "2015-11-23",
-            x_acs_security_token: creds.sts_security_token.as_deref(),
-            request_body: None,
-            style: &client.style,
-        };
-
-        let (headers, url_) =
-            get_openapi_request_header(&creds.access_key_secret, &creds.access_key_id, sign_params)
-                .map_err(|e| Error::Common(format!("get_common_headers error: {}", e)))?;
-
+        let query = serde_json::to_value(self)
+            .map_err(|e| Error::Common(format!("serialize query failed: {}", e)))?;
+        // 服务端可能已经发送了邮件时不重试，避免重复发送
         let resp = self
             .client
-            .http_client
-            .get(url_)
-            .headers(into_header_map(headers))
+            .api
+            .request("SingleSendMail", "2015-11-23")
+            .method(Method::GET)
+            .query(query)
+            .idempotent(false)
+            .build()
             .send()
             .await?;
-
-        let resp = parse_json_response(resp).await?;
         Ok(resp)
     }
 }
@@ -27,3 +27,24 @@ pub mod sts;
     feature = "sts"
 ))]
 pub mod credentials;
+
+/// 通用的阿里云OpenAPI客户端，用于调用sdk还没有封装的API
+#[cfg(any(
+    feature = "email",
+    feature = "oss",
+    feature = "translate",
+    feature = "sts"
+))]
+pub use u_sdk_common::open_api;
+
+/// 所有模块共用的错误类型，各个模块的`Error`都是它的重新导出
+pub use u_sdk_common::{Error, ServiceError};
+
+/// 可替换的http传输层，各个client的`http_client`可以传入自定义的`reqwest::Client`或mock
+pub use u_sdk_common::transport;
+
+/// 统一的重试策略，各个client可以通过`retry_policy`设置
+pub use u_sdk_common::retry;
+
+/// 敏感信息（AccessKey Secret、STS token、API key）的包装类型，`Debug`和`Display`输出`***`，drop时清零
+pub use u_sdk_common::secret;
@@ -2,15 +2,14 @@
 //!
 //! [阿里云API文档](https://help.aliyun.com/zh/oss/developer-reference/bucket-operations/)
 
-use super::Client;
 use super::sign_v4::HTTPVerb;
-use super::utils::{get_request_header, into_request_failed_error, parse_xml_response};
+use super::utils::{SignedBody, get_content_sha256, into_request_failed_error, parse_xml_response};
+use super::{Client, PayloadSigning};
 use crate::oss::Error;
 use bon::Builder;
 use serde::{Deserialize, Serialize};
 use serde_with::{DisplayFromStr, serde_as};
 use std::collections::HashMap;
-use url::Url;
 
 // region:    --- put bucket
 #[serde_with::skip_serializing_none]
@@ -46,26 +45,10 @@ struct CreateBucketConfiguration<'a> {
 impl PutBucket<'_> {
     pub async fn send(&self) -> Result<(), Error> {
         let client = self.client;
-        let request_url =
-            Url::parse(&format!("https://{}.{}", self.bucket_name, client.endpoint)).unwrap();
+        let request_url = client.bucket_url(self.bucket_name)?;
         let mut req_header_map: HashMap<String, String> =
             serde_json::from_value(serde_json::to_value(self).unwrap()).unwrap();
 
-        let creds = client.credentials_provider.load().await?;
-        if let Some(token) = &creds.sts_security_token {
-            req_header_map.insert("x-oss-security-token".to_string(), token.clone());
-        }
-
-        let header = get_request_header(
-            &creds.access_key_id,
-            &creds.access_key_secret,
-            req_header_map,
-            &request_url,
-            HTTPVerb::Put,
-            &client.region,
-            Some(self.bucket_name),
-        );
-
         let req_xml = {
             let create_conf = CreateBucketConfiguration {
                 storage_class: self.storage_class,
@@ -74,13 +57,24 @@ impl PutBucket<'_> {
 
             quick_xml::se::to_string(&create_conf).unwrap()
         };
+        if client.payload_signing == PayloadSigning::Signed {
+            req_header_map.insert(
+                "x-oss-content-sha256".to_owned(),
+                get_content_sha256(req_xml.as_bytes()),
+            );
+        }
+
+        let creds = client.load_credentials().await?;
 
         let resp = client
-            .http_client
-            .put(request_url)
-            .headers(header)
-            .body(req_xml)
-            .send()
+            .send_signed(
+                &creds,
+                HTTPVerb::Put,
+                request_url,
+                req_header_map,
+                client.signing_bucket(self.bucket_name),
+                SignedBody::Bytes(req_xml.into()),
+            )
             .await?;
 
         if !resp.status().is_success() {
@@ -166,33 +160,20 @@ impl ListObjectsV2<'_> {
         query_map.insert("list-type".to_owned(), "2".to_owned());
 
         let client = self.client;
-        let sign_url = Url::parse_with_params(
-            &format!("https://{}.{}/", client.bucket, client.endpoint),
-            query_map,
-        )
-        .unwrap();
-
-        let creds = client.credentials_provider.load().await?;
-        let mut req_header_map = HashMap::new();
-        if let Some(token) = &creds.sts_security_token {
-            req_header_map.insert("x-oss-security-token".to_string(), token.clone());
-        }
+        let mut sign_url = client.bucket_url(&client.bucket)?;
+        sign_url.query_pairs_mut().extend_pairs(query_map);
 
-        let header = get_request_header(
-            &creds.access_key_id,
-            &creds.access_key_secret,
-            req_header_map,
-            &sign_url,
-            HTTPVerb::Get,
-            &client.region,
-            Some(&client.bucket),
-        );
+        let creds = client.load_credentials().await?;
 
         let resp = client
-            .http_client
-            .get(sign_url)
-            .headers(header)
-            .send()
+            .send_signed(
+                &creds,
+                HTTPVerb::Get,
+                sign_url,
+                HashMap::new(),
+                client.signing_bucket(&client.bucket),
+                SignedBody::Empty,
+            )
             .await?;
 
         let res = parse_xml_response(resp).await?;
@@ -206,7 +187,8 @@ impl ListObjectsV2<'_> {
 pub struct GetBucketInfo<'a> {
     #[builder(start_fn)]
     pub(crate) client: &'a Client,
-    pub(crate) bucket: &'a str,
+    /// 默认为client的bucket
+    pub(crate) bucket: Option<&'a str>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -262,32 +244,21 @@ pub struct BucketPolicy {
 impl GetBucketInfo<'_> {
     pub async fn send(&self) -> Result<BucketInfo, Error> {
         let client = self.client;
-        let request_url = Url::parse_with_params(
-            &format!("https://{}.{}", self.bucket, client.endpoint),
-            [("bucketInfo", "")],
-        )
-        .unwrap();
-
-        let creds = client.credentials_provider.load().await?;
-        let mut req_header_map = HashMap::new();
-        if let Some(token) = &creds.sts_security_token {
-            req_header_map.insert("x-oss-security-token".to_string(), token.clone());
-        }
+        let bucket = self.bucket.unwrap_or(&client.bucket);
+        let mut request_url = client.bucket_url(bucket)?;
+        request_url.set_query(Some("bucketInfo"));
+
+        let creds = client.load_credentials().await?;
 
-        let header_map = get_request_header(
-            &creds.access_key_id,
-            &creds.access_key_secret,
-            req_header_map,
-            &request_url,
-            HTTPVerb::Get,
-            &client.region,
-            Some(&client.bucket),
-        );
         let resp = client
-            .http_client
-            .get(request_url)
-            .headers(header_map)
-            .send()
+            .send_signed(
+                &creds,
+                HTTPVerb::Get,
+                request_url,
+                HashMap::new(),
+                client.signing_bucket(bucket),
+                SignedBody::Empty,
+            )
             .await?;
 
         let res = parse_xml_response(resp).await?;
@@ -301,7 +272,8 @@ impl GetBucketInfo<'_> {
 pub struct GetBucketLocation<'a> {
     #[builder(start_fn)]
     pub(crate) client: &'a Client,
-    pub(crate) bucket: &'a str,
+    /// 默认为client的bucket
+    pub(crate) bucket: Option<&'a str>,
 }
 // xml数据为："<LocationConstraint>oss-cn-hangzhou</LocationConstraint>"，
 // 这种情况下使用xml反序列化比较特殊，写法得类似于下面这样：
@@ -315,32 +287,21 @@ impl GetBucketLocation<'_> {
     pub async fn send(&self) -> Result<String, Error> {
         let client = self.client;
 
-        let request_url = Url::parse_with_params(
-            &format!("https://{}.{}", self.bucket, client.endpoint),
-            [("location", "")],
-        )
-        .unwrap();
+        let bucket = self.bucket.unwrap_or(&client.bucket);
+        let mut request_url = client.bucket_url(bucket)?;
+        request_url.set_query(Some("location"));
 
-        let creds = client.credentials_provider.load().await?;
-        let mut req_header_map = HashMap::new();
-        if let Some(token) = &creds.sts_security_token {
-            req_header_map.insert("x-oss-security-token".to_string(), token.clone());
-        }
+        let creds = client.load_credentials().await?;
 
-        let header_map = get_request_header(
-            &creds.access_key_id,
-            &creds.access_key_secret,
-            req_header_map,
-            &request_url,
-            HTTPVerb::Get,
-            &client.region,
-            Some(&client.bucket),
-        );
         let resp = client
-            .http_client
-            .get(request_url)
-            .headers(header_map)
-            .send()
+            .send_signed(
+                &creds,
+                HTTPVerb::Get,
+                request_url,
+                HashMap::new(),
+                client.signing_bucket(bucket),
+                SignedBody::Empty,
+            )
             .await?;
 
         let res = parse_xml_response::<LocationConstraint>(resp).await?;
@@ -354,7 +315,8 @@ impl GetBucketLocation<'_> {
 pub struct GetBucketStat<'a> {
     #[builder(start_fn)]
     pub(crate) client: &'a Client,
-    pub(crate) bucket: &'a str,
+    /// 默认为client的bucket
+    pub(crate) bucket: Option<&'a str>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -385,33 +347,21 @@ pub struct BucketStat {
 impl GetBucketStat<'_> {
     pub async fn send(&self) -> Result<BucketStat, Error> {
         let client = self.client;
-        let request_url = Url::parse_with_params(
-            &format!("https://{}.{}", self.bucket, client.endpoint),
-            [("stat", "")],
-        )
-        .unwrap();
-
-        let creds = client.credentials_provider.load().await?;
-        let mut req_header_map = HashMap::new();
-        if let Some(token) = &creds.sts_security_token {
-            req_header_map.insert("x-oss-security-token".to_string(), token.clone());
-        }
+        let bucket = self.bucket.unwrap_or(&client.bucket);
+        let mut request_url = client.bucket_url(bucket)?;
+        request_url.set_query(Some("stat"));
 
-        let header_map = get_request_header(
-            &creds.access_key_id,
-            &creds.access_key_secret,
-            req_header_map,
-            &request_url,
-            HTTPVerb::Get,
-            &client.region,
-            Some(&client.bucket),
-        );
+        let creds = client.load_credentials().await?;
 
         let resp = client
-            .http_client
-            .get(request_url)
-            .headers(header_map)
-            .send()
+            .send_signed(
+                &creds,
+                HTTPVerb::Get,
+                request_url,
+                HashMap::new(),
+                client.signing_bucket(bucket),
+                SignedBody::Empty,
+            )
             .await?;
 
         let res = parse_xml_response(resp).await?;
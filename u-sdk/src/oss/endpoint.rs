@@ -0,0 +1,186 @@
+//! 访问地址相关：寻址方式、协议和endpoint
+//!
+//! [访问域名和数据中心](https://help.aliyun.com/zh/oss/user-guide/regions-and-endpoints)
+
+use super::{Client, Error};
+use url::Url;
+
+/// 请求OSS时URL的寻址方式
+///
+/// 无论使用哪种方式，签名时的CanonicalURI都为`/{bucket}/{object}`，由sdk自动处理
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AddressingStyle {
+    /// 虚拟主机方式：`https://{bucket}.{endpoint}/{object}`
+    #[default]
+    VirtualHosted,
+    /// 路径方式：`https://{endpoint}/{bucket}/{object}`，用于部分兼容S3风格的私有化网关
+    Path,
+    /// 自定义域名（CNAME）方式：`https://{domain}/{object}`
+    ///
+    /// - 自定义域名需要事先绑定到client的bucket上，访问其它bucket时仍使用虚拟主机方式
+    /// - 不涉及bucket的API（如`ListBuckets`，`DescribeRegions`）仍然使用`endpoint`访问
+    Cname(String),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Scheme {
+    Http,
+    #[default]
+    Https,
+}
+
+impl Scheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scheme::Http => "http",
+            Scheme::Https => "https",
+        }
+    }
+}
+
+/// OSS endpoint的类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EndpointType {
+    /// 外网访问：`oss-{region}.aliyuncs.com`
+    #[default]
+    Public,
+    /// 同地域ECS等内网访问：`oss-{region}-internal.aliyuncs.com`
+    Internal,
+    /// 传输加速：`oss-accelerate.aliyuncs.com`，需要bucket开启传输加速
+    Accelerate,
+}
+
+impl EndpointType {
+    /// 根据region生成对应的endpoint
+    ///
+    /// - `region`：如`cn-hangzhou`，不带`oss-`前缀
+    pub fn endpoint(&self, region: &str) -> String {
+        match self {
+            EndpointType::Public => format!("oss-{}.aliyuncs.com", region),
+            EndpointType::Internal => format!("oss-{}-internal.aliyuncs.com", region),
+            EndpointType::Accelerate => "oss-accelerate.aliyuncs.com".to_owned(),
+        }
+    }
+}
+
+impl Client {
+    /// 不涉及bucket的API使用的url：`{scheme}://{endpoint}/`
+    pub(crate) fn service_url(&self) -> Result<Url, Error> {
+        parse_url(&format!("{}://{}/", self.scheme.as_str(), self.endpoint))
+    }
+
+    /// bucket级别API使用的url，以`/`结尾
+    pub(crate) fn bucket_url(&self, bucket: &str) -> Result<Url, Error> {
+        let scheme = self.scheme.as_str();
+        let s = match &self.addressing_style {
+            AddressingStyle::VirtualHosted => format!("{}://{}.{}/", scheme, bucket, self.endpoint),
+            AddressingStyle::Path => format!("{}://{}/{}/", scheme, self.endpoint, bucket),
+            // 自定义域名只绑定到了client的bucket上，访问其它bucket时使用虚拟主机方式
+            AddressingStyle::Cname(domain) if bucket == self.bucket => {
+                format!("{}://{}/", scheme, domain)
+            }
+            AddressingStyle::Cname(_) => format!("{}://{}.{}/", scheme, bucket, self.endpoint),
+        };
+        parse_url(&s)
+    }
+
+    /// object级别API使用的url
+    ///
+    /// url不能添加`/`结尾，因为是否有`/`由object_name决定
+    pub(crate) fn object_url(&self, bucket: &str, object_name: &str) -> Result<Url, Error> {
+        let mut s = self.bucket_url(bucket)?.to_string();
+        s.push_str(object_name);
+        parse_url(&s)
+    }
+
+    /// 签名时需要传入的bucket
+    ///
+    /// 签名的CanonicalURI为`/{bucket}/{object}`，路径方式的url中本身就包含了bucket，此时返回`None`，
+    /// 由签名直接使用url的path
+    pub(crate) fn signing_bucket<'a>(&self, bucket: &'a str) -> Option<&'a str> {
+        match self.addressing_style {
+            AddressingStyle::Path => None,
+            _ => Some(bucket),
+        }
+    }
+}
+
+fn parse_url(s: &str) -> Result<Url, Error> {
+    Url::parse(s).map_err(|e| Error::Common(format!("invalid url `{}`: {}", s, e)))
+}
+
+/// 签名使用的host，非默认端口时需要带上端口，和实际发送请求时的`Host`保持一致
+pub(crate) fn host_header(url: &Url) -> Result<String, Error> {
+    let host = url
+        .host_str()
+        .ok_or_else(|| Error::Common(format!("url has no host: {}", url)))?;
+    Ok(match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_owned(),
+    })
+}
+
+#[test]
+fn addressing_style_url_test() {
+    use crate::credentials::{Credentials, StaticCredentialsProvider};
+    use std::sync::Arc;
+
+    let build = |style: AddressingStyle, scheme: Scheme| {
+        Client::builder()
+            .credentials_provider(Arc::new(StaticCredentialsProvider::new(Credentials::new(
+                "id", "secret", None, None,
+            ))))
+            .endpoint("oss-cn-hangzhou.aliyuncs.com")
+            .region("cn-hangzhou")
+            .bucket("example")
+            .addressing_style(style)
+            .scheme(scheme)
+            .build()
+    };
+
+    let client = build(AddressingStyle::VirtualHosted, Scheme::Https);
+    assert_eq!(
+        client.object_url("example", "a/b.txt").unwrap().as_str(),
+        "https://example.oss-cn-hangzhou.aliyuncs.com/a/b.txt"
+    );
+    assert_eq!(client.signing_bucket("example"), Some("example"));
+
+    let client = build(AddressingStyle::Path, Scheme::Http);
+    assert_eq!(
+        client.object_url("example", "a/b.txt").unwrap().as_str(),
+        "http://oss-cn-hangzhou.aliyuncs.com/example/a/b.txt"
+    );
+    assert_eq!(
+        client.bucket_url("example").unwrap().as_str(),
+        "http://oss-cn-hangzhou.aliyuncs.com/example/"
+    );
+    assert_eq!(client.signing_bucket("example"), None);
+
+    let client = build(
+        AddressingStyle::Cname("cdn.example.com".to_owned()),
+        Scheme::Https,
+    );
+    assert_eq!(
+        client.object_url("example", "a/b.txt").unwrap().as_str(),
+        "https://cdn.example.com/a/b.txt"
+    );
+    assert_eq!(
+        client.service_url().unwrap().as_str(),
+        "https://oss-cn-hangzhou.aliyuncs.com/"
+    );
+    // 其它bucket不使用自定义域名
+    let other = client.bucket_handle("other");
+    assert_eq!(other.bucket(), "other");
+    assert_eq!(other.addressing_style(), &AddressingStyle::VirtualHosted);
+    assert_eq!(
+        other.object_url("other", "a/b.txt").unwrap().as_str(),
+        "https://other.oss-cn-hangzhou.aliyuncs.com/a/b.txt"
+    );
+
+    let url = Url::parse("http://127.0.0.1:9000/example/a.txt").unwrap();
+    assert_eq!(host_header(&url).unwrap(), "127.0.0.1:9000");
+    assert_eq!(
+        EndpointType::Internal.endpoint("cn-hangzhou"),
+        "oss-cn-hangzhou-internal.aliyuncs.com"
+    );
+}
@@ -2,47 +2,138 @@
 
 pub mod bucket;
 pub mod object;
+pub mod presign;
 pub mod region;
 pub mod service;
 
-mod error;
+mod endpoint;
 
-pub use error::Error;
+pub use endpoint::{AddressingStyle, EndpointType, Scheme};
+pub use sign_v4::{HTTPVerb, PayloadSigning};
+pub use signer::Signer;
 use std::sync::Arc;
+use std::time::Duration;
+pub use u_sdk_common::Error;
 
+mod signer;
+
+pub(crate) mod sign_v1;
 pub(crate) mod sign_v4;
 pub(crate) mod utils;
 
-use crate::credentials::CredentialsProvider;
+use crate::credentials::{CredentialsProvider, DefaultCredentialsChain};
 use bon::bon;
+use u_sdk_common::clock::ClockOffset;
+use u_sdk_common::retry::RetryPolicy;
+use u_sdk_common::transport::{HttpTransport, transport_with_timeouts};
+
+/// OSS请求的签名版本
+///
+/// - [V4签名](https://help.aliyun.com/zh/oss/developer-reference/recommend-to-use-signature-version-4)
+/// - [V1签名](https://help.aliyun.com/zh/oss/developer-reference/include-signatures-in-the-authorization-header)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SignatureVersion {
+    /// `OSS AccessKeyId:Signature`，使用HMAC-SHA1
+    V1,
+    /// `OSS4-HMAC-SHA256`
+    #[default]
+    V4,
+}
 
+/// `Client`的clone开销很小，clone后的client共享同一个`CredentialsProvider`和http连接池
+#[derive(Clone)]
 pub struct Client {
     credentials_provider: Arc<dyn CredentialsProvider>,
     endpoint: String,
     region: String,
     bucket: String,
-    http_client: reqwest::Client,
+    addressing_style: AddressingStyle,
+    scheme: Scheme,
+    payload_signing: PayloadSigning,
+    signature_version: SignatureVersion,
+    clock_offset: ClockOffset,
+    http_client: Arc<dyn HttpTransport>,
+    retry_policy: RetryPolicy,
+    read_timeout: Option<Duration>,
 }
 
 /// 创建oss客户端
 #[bon]
 impl Client {
     /// region和endpoint：<https://help.aliyun.com/zh/oss/user-guide/regions-and-endpoints>
+    ///
+    /// - `credentials_provider`：默认为[`DefaultCredentialsChain`]
+    /// - `endpoint`：可以使用[`EndpointType::endpoint`]根据region生成外网、内网或传输加速的endpoint，
+    ///   也可以使用[`ClientBuilder::build_with_discovery`]在构建时根据bucket自动获取region和endpoint
+    /// - `addressing_style`：默认为[`AddressingStyle::VirtualHosted`]
+    /// - `scheme`：默认为[`Scheme::Https`]
+    /// - `payload_signing`：默认为[`PayloadSigning::Unsigned`]，对有请求体的API生效，
+    ///   `PutObject`、`AppendObject`可以单独设置
+    /// - `signature_version`：默认为[`SignatureVersion::V4`]，只支持V1签名的私有化部署或兼容网关可以使用V1
+    /// - `http_client`：`reqwest::Client`或其它[`HttpTransport`]，默认为`reqwest::Client::new()`，
+    ///   可以传入自定义超时、代理等配置的`reqwest::Client`，或者在多个client之间共享连接池
+    /// - `retry_policy`：重试策略，默认为[`RetryPolicy::default`]，默认只重试幂等的请求（POST除外），
+    ///   上传文件时每次重试都会重新读取文件
+    /// - `connect_timeout`、`read_timeout`、`timeout`：连接、读取和单次请求的总超时，
+    ///   只在没有指定`http_client`时生效，默认不限制。`read_timeout`同时作为
+    ///   [`object::GetObject::receive_bytes_stream`]的默认空闲超时，对传入的`http_client`也生效
     #[builder(on(String, into))]
     pub fn new(
+        #[builder(
+            default = Arc::new(DefaultCredentialsChain::new())
+        )]
         credentials_provider: Arc<dyn CredentialsProvider>,
         endpoint: String,
         region: String,
         bucket: String,
+        #[builder(default)] addressing_style: AddressingStyle,
+        #[builder(default)] scheme: Scheme,
+        #[builder(default)] payload_signing: PayloadSigning,
+        #[builder(default)] signature_version: SignatureVersion,
+        #[builder(with = |http_client: impl HttpTransport + 'static| Arc::new(http_client))]
+        http_client: Option<Arc<dyn HttpTransport>>,
+        #[builder(default)] retry_policy: RetryPolicy,
+        connect_timeout: Option<Duration>,
+        read_timeout: Option<Duration>,
+        timeout: Option<Duration>,
     ) -> Self {
+        let http_client = http_client
+            .unwrap_or_else(|| transport_with_timeouts(connect_timeout, read_timeout, timeout));
         Self {
             credentials_provider,
             endpoint,
             region,
             bucket,
-            http_client: reqwest::Client::new(),
+            addressing_style,
+            scheme,
+            payload_signing,
+            signature_version,
+            clock_offset: ClockOffset::new(),
+            http_client,
+            retry_policy,
+            read_timeout,
+        }
+    }
+
+    /// 返回一个操作其它bucket的client
+    ///
+    /// 返回的client与当前client共享`CredentialsProvider`、http连接池、region和endpoint，
+    /// 可用于所有Object和Bucket相关的操作，如跨bucket的`CopyObject`，查询其它bucket的信息等
+    ///
+    /// 如果当前client使用[`AddressingStyle::Cname`]，由于自定义域名只绑定到当前bucket，
+    /// 返回的client会使用[`AddressingStyle::VirtualHosted`]
+    pub fn bucket_handle(&self, bucket: impl Into<String>) -> Client {
+        let addressing_style = match &self.addressing_style {
+            AddressingStyle::Cname(_) => AddressingStyle::VirtualHosted,
+            style => style.clone(),
+        };
+        Client {
+            bucket: bucket.into(),
+            addressing_style,
+            ..self.clone()
         }
     }
+
     pub fn bucket(&self) -> &str {
         &self.bucket
     }
@@ -54,4 +145,34 @@ impl Client {
     pub fn endpoint(&self) -> &str {
         &self.endpoint
     }
+
+    pub fn addressing_style(&self) -> &AddressingStyle {
+        &self.addressing_style
+    }
+
+    pub fn scheme(&self) -> Scheme {
+        self.scheme
+    }
+
+    pub fn payload_signing(&self) -> PayloadSigning {
+        self.payload_signing
+    }
+
+    pub fn signature_version(&self) -> SignatureVersion {
+        self.signature_version
+    }
+
+    /// 签名使用的时钟偏移，服务端返回`RequestTimeTooSkewed`时会自动校正，
+    /// 通过[`Client::bucket_handle`]获取的client共享同一个偏移
+    pub fn clock_offset(&self) -> &ClockOffset {
+        &self.clock_offset
+    }
+
+    /// 请求单独设置的签名方式优先，否则使用client的设置
+    pub(crate) fn resolve_payload_signing(
+        &self,
+        per_request: Option<PayloadSigning>,
+    ) -> PayloadSigning {
+        per_request.unwrap_or(self.payload_signing)
+    }
 }
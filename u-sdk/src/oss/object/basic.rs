@@ -3,27 +3,26 @@
 //! [官方文档](https://help.aliyun.com/zh/oss/developer-reference/basic-operations-1/)
 
 use super::types_rs::*;
-use crate::oss::Client;
 use crate::oss::Error;
+use crate::oss::sign_v1::generate_v1_policy_signature;
 use crate::oss::sign_v4::HTTPVerb;
 use crate::oss::utils::{
-    PresignParams, compute_md5_from_file, generate_presigned_url, get_content_md5,
-    get_request_header, hmac_sha256_bytes, into_request_failed_error,
-    parse_get_object_response_header, parse_xml_response, utc_date_str, utc_date_time_str,
-    validate_object_name,
+    SignedBody, compute_digest_from_file, get_content_md5, get_content_sha256, hmac_sha256_bytes,
+    into_request_failed_error, parse_header, parse_xml_response, required_header, utc_date_str,
+    utc_date_time_str, validate_object_name,
 };
+use crate::oss::{Client, PayloadSigning, SignatureVersion};
 use base64::{Engine, engine::general_purpose};
 use bytes::Bytes;
+use reqwest::StatusCode;
 use reqwest::header::HeaderMap;
-use reqwest::{Body, StatusCode};
-use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::path::Path;
 use time::OffsetDateTime;
 use time::format_description::well_known::Iso8601;
 use tokio::io::AsyncWriteExt;
 use tokio_stream::{Stream, StreamExt};
-use tokio_util::io::ReaderStream;
+use u_sdk_common::timeout::{idle_timeout, with_timeout};
 
 impl<'a> PutObject<'a> {
     /// - `content_type`，不会进行MIME合法性检查
@@ -37,26 +36,29 @@ impl<'a> PutObject<'a> {
         validate_object_name(object_name)?;
 
         let client = self.client;
-        let request_url = url::Url::parse(&format!(
-            "https://{}.{}/{}", // url不能添加`/`结尾，因为是否有`/`由object_name决定
-            client.bucket, client.endpoint, object_name
-        ))
-        .unwrap();
+        let request_url = client.object_url(&client.bucket, object_name)?;
 
         let mut req_header_map: HashMap<String, String> =
             serde_json::from_value(serde_json::to_value(self).unwrap()).unwrap();
         // 添加api剩下的请求头
-        match &object {
+        let sign_payload =
+            client.resolve_payload_signing(self.payload_signing) == PayloadSigning::Signed;
+        let content_sha256 = match &object {
             PutObjectBody::Bytes(bytes) => {
                 req_header_map.insert("content-md5".to_owned(), get_content_md5(bytes.as_slice()));
                 req_header_map.insert("content-length".to_owned(), bytes.len().to_string());
+                sign_payload.then(|| get_content_sha256(bytes))
             }
             PutObjectBody::FilePath(path) => {
                 let file_size = std::fs::metadata(path)?.len();
                 req_header_map.insert("content-length".to_owned(), file_size.to_string());
-                let md5_str = compute_md5_from_file(path).await?;
+                let (md5_str, sha256_str) = compute_digest_from_file(path, sign_payload).await?;
                 req_header_map.insert("content-md5".to_owned(), md5_str);
+                sha256_str
             }
+        };
+        if let Some(content_sha256) = content_sha256 {
+            req_header_map.insert("x-oss-content-sha256".to_owned(), content_sha256);
         }
 
         // 如果有x-meta-*，将其添加到请求头中
@@ -88,37 +90,22 @@ impl<'a> PutObject<'a> {
             }
         }
 
-        let creds = client.credentials_provider.load().await?;
-        // sts token
-        if let Some(token) = &creds.sts_security_token {
-            req_header_map.insert("x-oss-security-token".to_owned(), token.clone());
-        }
-
-        let header_map = get_request_header(
-            &creds.access_key_id,
-            &creds.access_key_secret,
-            req_header_map,
-            &request_url,
-            HTTPVerb::Put,
-            &client.region,
-            Some(&client.bucket),
-        );
+        let creds = client.load_credentials().await?;
 
-        let data = match object {
-            PutObjectBody::Bytes(bytes) => Body::from(bytes),
-            PutObjectBody::FilePath(path) => {
-                let file = tokio::fs::File::open(path).await?;
-                let stream = ReaderStream::new(file);
-                Body::wrap_stream(stream)
-            }
+        let body = match object {
+            PutObjectBody::Bytes(bytes) => SignedBody::Bytes(bytes.into()),
+            PutObjectBody::FilePath(path) => SignedBody::File(path),
         };
 
         let resp = client
-            .http_client
-            .put(request_url)
-            .headers(header_map)
-            .body(data)
-            .send()
+            .send_signed(
+                &creds,
+                HTTPVerb::Put,
+                request_url,
+                req_header_map,
+                client.signing_bucket(&client.bucket),
+                body,
+            )
             .await?;
         // println!("response: {:#?}", resp);
 
@@ -128,26 +115,10 @@ impl<'a> PutObject<'a> {
         }
 
         let header = resp.headers();
-        let content_md5 = header
-            .get("Content-MD5")
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_owned();
-        let x_oss_hash_crc64ecma = header
-            .get("x-oss-hash-crc64ecma")
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_owned();
-        let x_oss_version_id = header
-            .get("x-oss-version-id")
-            .map(|v| v.to_str().unwrap().to_owned());
-
         Ok(PutObjectResponseHeader {
-            content_md5,
-            x_oss_hash_crc64ecma,
-            x_oss_version_id,
+            content_md5: required_header(header, "content-md5")?,
+            x_oss_hash_crc64ecma: required_header(header, "x-oss-hash-crc64ecma")?,
+            x_oss_version_id: parse_header(header, "x-oss-version-id")?,
         })
     }
 
@@ -177,11 +148,7 @@ impl<'a> PutObject<'a> {
         validate_object_name(object_name)?;
 
         let client = self.client;
-        let mut base_url = url::Url::parse(&format!(
-            "https://{}.{}/{}",
-            client.bucket, client.endpoint, object_name
-        ))
-        .unwrap();
+        let mut base_url = client.object_url(&client.bucket, object_name)?;
 
         // callback处理
         if let Some(oss_callback) = &self.callback {
@@ -205,14 +172,6 @@ impl<'a> PutObject<'a> {
             }
         }
 
-        let creds = client.credentials_provider.load().await?;
-        // sts token
-        if let Some(token) = &creds.sts_security_token {
-            base_url
-                .query_pairs_mut()
-                .append_pair("x-oss-security-token", token);
-        }
-
         let mut header_map: HashMap<String, String> =
             serde_json::from_value(serde_json::to_value(self).unwrap()).unwrap();
         if !self.custom_metas.is_empty() {
@@ -224,18 +183,9 @@ impl<'a> PutObject<'a> {
             header_map.extend(custom_meta_map);
         };
 
-        let presigned_params = PresignParams {
-            access_key_id: &creds.access_key_id,
-            access_key_secret: &creds.access_key_secret,
-            header_map,
-            presigned_url: base_url,
-            http_verb: HTTPVerb::Put,
-            url_expires: expires,
-            bucket: &client.bucket,
-            signing_region: &client.region,
-        };
-        let signed_url = generate_presigned_url(presigned_params);
-        Ok(signed_url)
+        client
+            .presigned_url(HTTPVerb::Put, base_url, header_map, expires)
+            .await
     }
 }
 
@@ -243,6 +193,7 @@ impl PostObject<'_> {
     /// 生成用于浏览器表单方式上传所需要的内容
     ///
     /// - [oss Post v4 签名文档](https://help.aliyun.com/zh/oss/developer-reference/signature-version-4-recommend)
+    /// - client使用[`SignatureVersion::V1`]时生成V1签名的policy，表单字段见[`GeneratePolicyResult::form_fields`]
     /// - [PostObject API文档](https://help.aliyun.com/zh/oss/developer-reference/postobject)
     ///
     /// # 参数
@@ -255,16 +206,25 @@ impl PostObject<'_> {
         expiration: OffsetDateTime,
     ) -> Result<GeneratePolicyResult, Error> {
         let policy_expiration = expiration.to_utc().format(&Iso8601::DEFAULT).unwrap();
-        let now = OffsetDateTime::now_utc();
+        let now = self.client.clock_offset.now();
         // 这个date，需不需utc，文档没说...
         let date = utc_date_str(&now);
         let date_time = utc_date_time_str(&now);
         let client = self.client;
-        let creds = client.credentials_provider.load().await?;
-        let credential = format!(
-            "{}/{}/{}/oss/aliyun_v4_request",
-            creds.access_key_id, date, client.region
-        );
+        // 表单提交的地址，policy中限制了bucket时使用该bucket
+        let url = client
+            .bucket_url(self.bucket.as_deref().unwrap_or(&client.bucket))?
+            .to_string();
+        let creds = client.load_credentials().await?;
+        let is_v4 = client.signature_version == SignatureVersion::V4;
+        let credential = is_v4.then(|| {
+            format!(
+                "{}/{}/{}/oss/aliyun_v4_request",
+                creds.access_key_id, date, client.region
+            )
+        });
+        let signature_version = is_v4.then(|| "OSS4-HMAC-SHA256".to_owned());
+        let date_time = is_v4.then_some(date_time);
 
         // 处理callback相关
         let mut callback_b64 = None;
@@ -288,9 +248,12 @@ impl PostObject<'_> {
             expiration: policy_expiration,
             conditions: PostPolicyCondition {
                 bucket: self.bucket,
-                x_oss_signature_version: "OSS4-HMAC-SHA256".to_owned(),
+                x_oss_signature_version: signature_version.clone(),
                 x_oss_credential: credential.clone(),
-                x_oss_security_token: creds.sts_security_token.clone(),
+                x_oss_security_token: creds
+                    .sts_security_token
+                    .as_ref()
+                    .map(|token| token.expose_secret().to_owned()),
                 x_oss_date: date_time.clone(),
                 content_length_range: self.content_length_range,
                 key: self.key,
@@ -314,21 +277,32 @@ impl PostObject<'_> {
         };
         let policy_str = serde_json::to_string(&policy).unwrap();
         let encoded_policy = general_purpose::STANDARD.encode(policy_str.as_bytes());
-        let date_key = hmac_sha256_bytes(
-            format!("aliyun_v4{}", creds.access_key_secret).as_bytes(),
-            &date,
-        );
-        let date_region_key = hmac_sha256_bytes(&date_key, &client.region);
-        let date_region_service_key = hmac_sha256_bytes(&date_region_key, "oss");
-        let signing_key = hmac_sha256_bytes(&date_region_service_key, "aliyun_v4_request");
-        let signature = hex::encode(hmac_sha256_bytes(&signing_key, &encoded_policy));
+        let signature = match client.signature_version {
+            SignatureVersion::V1 => generate_v1_policy_signature(
+                creds.access_key_secret.expose_secret(),
+                &encoded_policy,
+            ),
+            SignatureVersion::V4 => {
+                let date_key = hmac_sha256_bytes(
+                    format!("aliyun_v4{}", creds.access_key_secret.expose_secret()).as_bytes(),
+                    &date,
+                );
+                let date_region_key = hmac_sha256_bytes(&date_key, &client.region);
+                let date_region_service_key = hmac_sha256_bytes(&date_region_key, "oss");
+                let signing_key = hmac_sha256_bytes(&date_region_service_key, "aliyun_v4_request");
+                hex::encode(hmac_sha256_bytes(&signing_key, &encoded_policy))
+            }
+        };
 
         Ok(GeneratePolicyResult {
+            url,
+            signature_version: client.signature_version,
             policy: encoded_policy,
             x_oss_signature: signature,
             x_oss_date: date_time,
             x_oss_credential: credential,
-            x_oss_signature_version: "OSS4-HMAC-SHA256".to_owned(),
+            x_oss_signature_version: signature_version,
+            oss_access_key_id: (!is_v4).then(|| creds.access_key_id.clone()),
             x_oss_security_token: creds.sts_security_token.clone(),
             callback: callback_b64,
             callback_var,
@@ -338,16 +312,20 @@ impl PostObject<'_> {
 
 impl GetObject<'_> {
     /// 返回：
-    /// - `Vec<u8>`：文件数据
-    /// - `HashMap<String, String>`：所有响应头
+    /// - `Bytes`：文件数据
+    /// - `ObjectMeta`：解析后的响应头
+    /// - `HeaderMap`：所有原始响应头
     pub async fn receive_bytes(
         &self,
         object_name: &str,
-    ) -> Result<(Bytes, GetObjectResponseHeader, HeaderMap), Error> {
-        let (resp, response_header, header) = self.get_response(object_name).await?;
-        let data = resp.bytes().await?;
+    ) -> Result<(Bytes, ObjectMeta, HeaderMap), Error> {
+        with_timeout(self.timeout, async {
+            let (resp, response_header, header) = self.get_response(object_name).await?;
+            let data = resp.bytes().await?;
 
-        Ok((data, response_header, header))
+            Ok((data, response_header, header))
+        })
+        .await
     }
 
     pub async fn receive_bytes_stream(
@@ -356,30 +334,35 @@ impl GetObject<'_> {
     ) -> Result<
         (
             impl Stream<Item = Result<Bytes, Error>> + use<>,
-            GetObjectResponseHeader,
+            ObjectMeta,
             HeaderMap,
         ),
         Error,
     > {
-        let (resp, response_header, header) = self.get_response(object_name).await?;
+        let (resp, response_header, header) =
+            with_timeout(self.timeout, self.get_response(object_name)).await?;
         let byte_stream = resp.bytes_stream().map(|item| item.map_err(Error::Reqwest));
-        Ok((byte_stream, response_header, header))
+        let idle = self.idle_timeout.or(self.client.read_timeout);
+        Ok((idle_timeout(byte_stream, idle), response_header, header))
     }
 
     pub async fn download_to_file(
         &self,
         object_name: &str,
         file_path: &Path,
-    ) -> Result<(GetObjectResponseHeader, HeaderMap), Error> {
-        let (mut resp, response_header, header) = self.get_response(object_name).await?;
+    ) -> Result<(ObjectMeta, HeaderMap), Error> {
+        with_timeout(self.timeout, async {
+            let (mut resp, response_header, header) = self.get_response(object_name).await?;
 
-        let mut file = tokio::fs::File::create(file_path).await?;
-        while let Some(chunk) = resp.chunk().await? {
-            file.write_all(&chunk).await?;
-        }
-        file.flush().await?;
+            let mut file = tokio::fs::File::create(file_path).await?;
+            while let Some(chunk) = resp.chunk().await? {
+                file.write_all(&chunk).await?;
+            }
+            file.flush().await?;
 
-        Ok((response_header, header))
+            Ok((response_header, header))
+        })
+        .await
     }
 
     /// 生成预签名URL
@@ -399,79 +382,46 @@ impl GetObject<'_> {
         validate_object_name(object_name)?;
 
         let client = self.client;
-        let mut base_url = url::Url::parse(&format!(
-            "https://{}.{}/{}",
-            client.bucket, client.endpoint, object_name
-        ))
-        .unwrap();
+        let mut base_url = client.object_url(&client.bucket, object_name)?;
         // 先把所有query参数添加到url中，这样在签名的时候直接传递url即可获取所有query参数
         let query_map: HashMap<String, String> =
             serde_json::from_value(serde_json::to_value(self.queries_part()).unwrap()).unwrap();
         for (k, v) in query_map.iter() {
             base_url.query_pairs_mut().append_pair(k, v);
         }
-        let creds = client.credentials_provider.load().await?;
-        if let Some(token) = &creds.sts_security_token {
-            base_url
-                .query_pairs_mut()
-                .append_pair("x-oss-security-token", token);
-        }
-
         let header_map: HashMap<String, String> =
             serde_json::from_value(serde_json::to_value(self.headers_part()).unwrap()).unwrap();
-        let presigned_params = PresignParams {
-            access_key_id: &creds.access_key_id,
-            access_key_secret: &creds.access_key_secret,
-            header_map,
-            presigned_url: base_url,
-            http_verb: HTTPVerb::Get,
-            url_expires: expires,
-            bucket: &client.bucket,
-            signing_region: &client.region,
-        };
-        let signed_url = generate_presigned_url(presigned_params);
-        Ok(signed_url)
+        client
+            .presigned_url(HTTPVerb::Get, base_url, header_map, expires)
+            .await
     }
 
     async fn get_response(
         &self,
         object_name: &str,
-    ) -> Result<(reqwest::Response, GetObjectResponseHeader, HeaderMap), Error> {
+    ) -> Result<(reqwest::Response, ObjectMeta, HeaderMap), Error> {
         validate_object_name(object_name)?;
 
         let client = self.client;
-        let mut request_url = url::Url::parse(&format!(
-            "https://{}.{}/{}",
-            client.bucket, client.endpoint, object_name
-        ))
-        .unwrap();
+        let mut request_url = client.object_url(&client.bucket, object_name)?;
         let query_map: HashMap<String, String> =
             serde_json::from_value(serde_json::to_value(self.queries_part()).unwrap()).unwrap();
         for (k, v) in query_map.iter() {
             request_url.query_pairs_mut().append_pair(k, v);
         }
 
-        let mut req_header_map: HashMap<String, String> =
+        let req_header_map: HashMap<String, String> =
             serde_json::from_value(serde_json::to_value(self.headers_part()).unwrap()).unwrap();
-        let creds = client.credentials_provider.load().await?;
-        if let Some(token) = &creds.sts_security_token {
-            req_header_map.insert("x-oss-security-token".to_owned(), token.clone());
-        }
-        let header_map = get_request_header(
-            &creds.access_key_id,
-            &creds.access_key_secret,
-            req_header_map,
-            &request_url,
-            HTTPVerb::Get,
-            &client.region,
-            Some(&client.bucket),
-        );
-
+        let creds = client.load_credentials().await?;
         let resp = client
-            .http_client
-            .get(request_url)
-            .headers(header_map)
-            .send()
+            .send_signed(
+                &creds,
+                HTTPVerb::Get,
+                request_url,
+                req_header_map,
+                client.signing_bucket(&client.bucket),
+                SignedBody::Empty,
+            )
             .await?;
 
         let status = resp.status();
@@ -480,11 +430,7 @@ impl GetObject<'_> {
         }
 
         let header = resp.headers().clone();
-        let (mut response_header, custom_meta_map) =
-            parse_get_object_response_header::<GetObjectResponseHeader>(&header);
-        if !custom_meta_map.is_empty() {
-            response_header.custom_x_oss_meta = custom_meta_map;
-        }
+        let response_header = ObjectMeta::from_header_map(&header)?;
 
         Ok((resp, response_header, header))
     }
@@ -501,33 +447,20 @@ impl CopyObject<'_> {
         validate_object_name(dest_object_name)?;
 
         let client = self.client;
-        let request_url = url::Url::parse(&format!(
-            "https://{}.{}/{}",
-            dest_bucket, client.endpoint, dest_object_name
-        ))
-        .unwrap();
+        let request_url = client.object_url(dest_bucket, dest_object_name)?;
 
-        let mut req_header_map: HashMap<String, String> =
+        let req_header_map: HashMap<String, String> =
             serde_json::from_value(serde_json::to_value(self).unwrap()).unwrap();
-        let creds = client.credentials_provider.load().await?;
-        if let Some(token) = &creds.sts_security_token {
-            req_header_map.insert("x-oss-security-token".to_owned(), token.clone());
-        }
-        let header_map = get_request_header(
-            &creds.access_key_id,
-            &creds.access_key_secret,
-            req_header_map,
-            &request_url,
-            HTTPVerb::Put,
-            &client.region,
-            Some(&client.bucket),
-        );
-
+        let creds = client.load_credentials().await?;
         let resp = client
-            .http_client
-            .put(request_url)
-            .headers(header_map)
-            .send()
+            .send_signed(
+                &creds,
+                HTTPVerb::Put,
+                request_url,
+                req_header_map,
+                client.signing_bucket(dest_bucket),
+                SignedBody::Empty,
+            )
             .await?;
 
         let data = parse_xml_response(resp).await?;
@@ -547,14 +480,11 @@ impl AppendObject<'_> {
         validate_object_name(object_name)?;
 
         let client = self.client;
-        let request_url = url::Url::parse_with_params(
-            &format!(
-                "https://{}.{}/{}",
-                client.bucket, client.endpoint, object_name
-            ),
-            [("append", ""), ("position", &position.to_string())],
-        )
-        .unwrap();
+        let mut request_url = client.object_url(&client.bucket, object_name)?;
+        request_url
+            .query_pairs_mut()
+            .append_pair("append", "")
+            .append_pair("position", &position.to_string());
 
         let mut req_header_map: HashMap<String, String> =
             serde_json::from_value(serde_json::to_value(self).unwrap()).unwrap();
@@ -570,49 +500,29 @@ impl AppendObject<'_> {
 
         req_header_map.insert("content-md5".to_owned(), get_content_md5(&data));
         req_header_map.insert("content-length".to_owned(), data.len().to_string());
-
-        let creds = client.credentials_provider.load().await?;
-        if let Some(token) = &creds.sts_security_token {
-            req_header_map.insert("x-oss-security-token".to_owned(), token.clone());
+        if client.resolve_payload_signing(self.payload_signing) == PayloadSigning::Signed {
+            req_header_map.insert("x-oss-content-sha256".to_owned(), get_content_sha256(&data));
         }
 
-        let header_map = get_request_header(
-            &creds.access_key_id,
-            &creds.access_key_secret,
-            req_header_map,
-            &request_url,
-            HTTPVerb::Post,
-            &client.region,
-            Some(&client.bucket),
-        );
+        let creds = client.load_credentials().await?;
 
         let resp = client
-            .http_client
-            .post(request_url)
-            .headers(header_map)
-            .body(data)
-            .send()
+            .send_signed(
+                &creds,
+                HTTPVerb::Post,
+                request_url,
+                req_header_map,
+                client.signing_bucket(&client.bucket),
+                SignedBody::Bytes(data.into()),
+            )
             .await?;
 
         if !resp.status().is_success() {
             return Err(into_request_failed_error(resp).await);
         }
 
-        let next_position = resp
-            .headers()
-            .get("x-oss-next-append-position")
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .parse::<u64>()
-            .unwrap();
-        let response_hash = resp
-            .headers()
-            .get("x-oss-hash-crc64ecma")
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_owned();
+        let next_position = required_header(resp.headers(), "x-oss-next-append-position")?;
+        let response_hash = required_header(resp.headers(), "x-oss-hash-crc64ecma")?;
 
         Ok((next_position, response_hash))
     }
@@ -624,11 +534,8 @@ impl DeleteMultipleObjects<'_> {
     pub async fn send(&self) -> Result<Option<DeleteResult>, Error> {
         let client = self.client;
 
-        let request_url = url::Url::parse(&format!(
-            "https://{}.{}/?delete",
-            client.bucket, client.endpoint
-        ))
-        .unwrap();
+        let mut request_url = client.bucket_url(&client.bucket)?;
+        request_url.set_query(Some("delete"));
         let delete_req = DeleteMultipleObjectsRequest {
             quiet: self.quiet,
             object: &self.objects,
@@ -643,28 +550,24 @@ impl DeleteMultipleObjects<'_> {
             "content-md5".to_owned(),
             get_content_md5(req_body.as_bytes()),
         );
-
-        let creds = client.credentials_provider.load().await?;
-        if let Some(token) = &creds.sts_security_token {
-            req_header_map.insert("x-oss-security-token".to_owned(), token.clone());
+        if client.payload_signing == PayloadSigning::Signed {
+            req_header_map.insert(
+                "x-oss-content-sha256".to_owned(),
+                get_content_sha256(req_body.as_bytes()),
+            );
         }
 
-        let header_map = get_request_header(
-            &creds.access_key_id,
-            &creds.access_key_secret,
-            req_header_map,
-            &request_url,
-            HTTPVerb::Post,
-            &client.region,
-            Some(&client.bucket),
-        );
+        let creds = client.load_credentials().await?;
 
         let resp = client
-            .http_client
-            .post(request_url)
-            .headers(header_map)
-            .body(req_body)
-            .send()
+            .send_signed(
+                &creds,
+                HTTPVerb::Post,
+                request_url,
+                req_header_map,
+                client.signing_bucket(&client.bucket),
+                SignedBody::Bytes(req_body.into()),
+            )
             .await?;
 
         // 如果是is_quiet为true的请求，返回的xml中没有删除结果，使用Option来简化处理
@@ -674,41 +577,25 @@ impl DeleteMultipleObjects<'_> {
 }
 
 impl HeadObject<'_> {
-    pub async fn send(
-        &self,
-        object_name: &str,
-    ) -> Result<(HeadObjectResponseHeader, HeaderMap), Error> {
+    pub async fn send(&self, object_name: &str) -> Result<(ObjectMeta, HeaderMap), Error> {
         validate_object_name(object_name)?;
 
         let client = self.client;
-        let request_url = url::Url::parse(&format!(
-            "https://{}.{}/{}",
-            client.bucket, client.endpoint, object_name
-        ))
-        .unwrap();
+        let request_url = client.object_url(&client.bucket, object_name)?;
 
-        let mut req_header_map: HashMap<String, String> =
+        let req_header_map: HashMap<String, String> =
             serde_json::from_value(serde_json::to_value(self).unwrap()).unwrap();
-        let creds = client.credentials_provider.load().await?;
-        if let Some(token) = &creds.sts_security_token {
-            req_header_map.insert("x-oss-security-token".to_owned(), token.clone());
-        }
-
-        let header_map = get_request_header(
-            &creds.access_key_id,
-            &creds.access_key_secret,
-            req_header_map,
-            &request_url,
-            HTTPVerb::Head,
-            &client.region,
-            Some(&client.bucket),
-        );
+        let creds = client.load_credentials().await?;
 
         let resp = client
-            .http_client
-            .head(request_url)
-            .headers(header_map)
-            .send()
+            .send_signed(
+                &creds,
+                HTTPVerb::Head,
+                request_url,
+                req_header_map,
+                client.signing_bucket(&client.bucket),
+                SignedBody::Empty,
+            )
             .await?;
 
         let status = resp.status();
@@ -717,13 +604,30 @@ impl HeadObject<'_> {
         }
 
         let header = resp.headers().clone();
-        let (mut response_header, custom_meta_map) =
-            parse_get_object_response_header::<HeadObjectResponseHeader>(&header);
-        if !custom_meta_map.is_empty() {
-            response_header.custom_x_oss_meta = custom_meta_map;
-        }
+        let response_header = ObjectMeta::from_header_map(&header)?;
         Ok((response_header, header))
     }
+
+    /// 生成使用HEAD方法的预签名URL
+    ///
+    /// 设置的条件header（如`if_match`）会参与签名，使用URL时需要携带相同的header
+    ///
+    /// - `expires`：URL过期时间，单位秒，取值要求同[`GetObject::generate_presigned_url`]
+    pub async fn generate_presigned_url(
+        &self,
+        object_name: &str,
+        expires: i32,
+    ) -> Result<String, Error> {
+        validate_object_name(object_name)?;
+
+        let client = self.client;
+        let base_url = client.object_url(&client.bucket, object_name)?;
+        let header_map: HashMap<String, String> =
+            serde_json::from_value(serde_json::to_value(self).unwrap()).unwrap();
+        client
+            .presigned_url(HTTPVerb::Head, base_url, header_map, expires)
+            .await
+    }
 }
 
 /// Object基础操作
@@ -755,51 +659,28 @@ impl Client {
     ) -> Result<DeleteObjectResponseHeader, Error> {
         validate_object_name(object_name)?;
 
-        let request_url = url::Url::parse(&format!(
-            "https://{}.{}/{}",
-            self.bucket, self.endpoint, object_name
-        ))
-        .unwrap();
-
-        let creds = self.credentials_provider.load().await?;
-        let mut req_header_map = HashMap::new();
-        if let Some(token) = &creds.sts_security_token {
-            req_header_map.insert("x-oss-security-token".to_owned(), token.clone());
-        }
+        let request_url = self.object_url(&self.bucket, object_name)?;
 
-        let header_map = get_request_header(
-            &creds.access_key_id,
-            &creds.access_key_secret,
-            req_header_map,
-            &request_url,
-            HTTPVerb::Delete,
-            &self.region,
-            Some(&self.bucket),
-        );
+        let creds = self.load_credentials().await?;
 
         let resp = self
-            .http_client
-            .delete(request_url)
-            .headers(header_map)
-            .send()
+            .send_signed(
+                &creds,
+                HTTPVerb::Delete,
+                request_url,
+                HashMap::new(),
+                self.signing_bucket(&self.bucket),
+                SignedBody::Empty,
+            )
             .await?;
 
         if !resp.status().is_success() {
             return Err(into_request_failed_error(resp).await);
         }
 
-        let x_oss_delete_marker = resp
-            .headers()
-            .get("x-oss-delete-marker")
-            .map(|v| v.to_str().unwrap().parse::<bool>().unwrap());
-        let x_oss_version_id = resp
-            .headers()
-            .get("x-oss-version-id")
-            .map(|v| v.to_str().unwrap().to_owned());
-
         Ok(DeleteObjectResponseHeader {
-            x_oss_delete_marker,
-            x_oss_version_id,
+            x_oss_delete_marker: parse_header(resp.headers(), "x-oss-delete-marker")?,
+            x_oss_version_id: parse_header(resp.headers(), "x-oss-version-id")?,
         })
     }
 
@@ -811,40 +692,24 @@ impl Client {
         HeadObject::builder(self)
     }
 
-    /// - 这里返回`HashMap`而没有返回struct，主要考虑到response header中有一些参数文档中没说出来，不便于转化为指定的struct
-    /// - 返回的`HashMap`中所有的`key`均为小写，这里代码并没有使用`to_lowercase`，因为`reqwest`获取的header都为小写
-    pub async fn get_object_meta(
-        &self,
-        object_name: &str,
-    ) -> Result<GetObjectMetaResponseHeader, Error> {
+    /// 只返回部分元数据：`content_length`，`etag`，`last_modified`，`x_oss_last_access_time`，
+    /// `x_oss_transition_time`，`x_oss_version_id`等，其它字段为`None`；需要完整元数据请使用[`HeadObject`]
+    pub async fn get_object_meta(&self, object_name: &str) -> Result<ObjectMeta, Error> {
         validate_object_name(object_name)?;
 
-        let request_url = url::Url::parse(&format!(
-            "https://{}.{}/{}?objectMeta",
-            self.bucket, self.endpoint, object_name
-        ))
-        .unwrap();
-
-        let creds = self.credentials_provider.load().await?;
-        let mut req_header_map = HashMap::new();
-        if let Some(token) = &creds.sts_security_token {
-            req_header_map.insert("x-oss-security-token".to_owned(), token.clone());
-        }
-        let header_map = get_request_header(
-            &creds.access_key_id,
-            &creds.access_key_secret,
-            req_header_map,
-            &request_url,
-            HTTPVerb::Head,
-            &self.region,
-            Some(&self.bucket),
-        );
+        let mut request_url = self.object_url(&self.bucket, object_name)?;
+        request_url.set_query(Some("objectMeta"));
 
+        let creds = self.load_credentials().await?;
         let resp = self
-            .http_client
-            .head(request_url)
-            .headers(header_map)
-            .send()
+            .send_signed(
+                &creds,
+                HTTPVerb::Head,
+                request_url,
+                HashMap::new(),
+                self.signing_bucket(&self.bucket),
+                SignedBody::Empty,
+            )
             .await?;
 
         let status = resp.status();
@@ -852,15 +717,27 @@ impl Client {
             return Err(into_request_failed_error(resp).await);
         }
 
-        let mut response_header = Map::new();
-        for (name, val) in resp.headers().iter() {
-            let name_s = name.as_str();
-            if let Ok(s) = val.to_str() {
-                response_header.insert(name_s.to_string(), Value::String(s.to_string()));
-            }
-        }
-
-        let data = serde_json::from_value(Value::Object(response_header)).unwrap();
-        Ok(data)
+        ObjectMeta::from_header_map(resp.headers())
     }
 }
+
+#[test]
+fn send_future_is_send_test() {
+    // 上传和下载需要能够在`tokio::spawn`中使用
+    use crate::credentials::{Credentials, StaticCredentialsProvider};
+    use std::sync::Arc;
+
+    fn assert_send<T: Send>(_: T) {}
+    let client = Client::builder()
+        .credentials_provider(Arc::new(StaticCredentialsProvider::new(Credentials::new(
+            "id", "secret", None, None,
+        ))))
+        .endpoint("oss-cn-hangzhou.aliyuncs.com")
+        .region("cn-hangzhou")
+        .bucket("example")
+        .build();
+    let put_object = client.put_object().build();
+    assert_send(put_object.send("a.txt", PutObjectBody::Bytes(vec![])));
+    let get_object = client.get_object().build();
+    assert_send(get_object.receive_bytes_stream("a.txt"));
+}
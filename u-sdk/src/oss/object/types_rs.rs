@@ -1,15 +1,20 @@
-use super::super::Client;
+use super::super::{Client, PayloadSigning, SignatureVersion};
 use crate::oss::Error;
-use crate::oss::utils::validate_object_name;
+use crate::oss::utils::{
+    parse_gmt, parse_gmt_header, parse_header, required_header, validate_object_name,
+};
 use bon::Builder;
 use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
+use reqwest::header::HeaderMap;
 use serde::{Deserialize, Serialize, Serializer};
 use serde_json::{Value, json};
-use serde_with::{DisplayFromStr, serde_as};
 use std::collections::HashMap;
 use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
 use time::OffsetDateTime;
 use time::format_description::well_known::Rfc2822;
+use u_sdk_common::secret::Secret;
 
 // region:    --- pub object
 /// Header字段中：
@@ -57,6 +62,10 @@ pub struct PutObject<'a> {
     // callback
     #[serde(skip_serializing)]
     pub(crate) callback: Option<OssCallBack>,
+
+    /// 不设置时使用client的[`PayloadSigning`]，对`generate_presigned_url`无效
+    #[serde(skip_serializing)]
+    pub(crate) payload_signing: Option<PayloadSigning>,
 }
 
 pub trait OssMetaExt<'a>: Sized {
@@ -187,11 +196,11 @@ pub(crate) struct PostPolicy<'a> {
 pub(crate) struct PostPolicyCondition<'a> {
     // POST v4签名表单元素（字段）
     pub(crate) bucket: Option<String>,
-    // 固定为`OSS4-HMAC-SHA256`，自动添加
-    pub(crate) x_oss_signature_version: String,
-    pub(crate) x_oss_credential: String,
+    // 固定为`OSS4-HMAC-SHA256`，自动添加；这几个字段只有V4签名才有
+    pub(crate) x_oss_signature_version: Option<String>,
+    pub(crate) x_oss_credential: Option<String>,
     pub(crate) x_oss_security_token: Option<String>,
-    pub(crate) x_oss_date: String,
+    pub(crate) x_oss_date: Option<String>,
     // (min, max)
     pub(crate) content_length_range: Option<(i32, i32)>,
     pub(crate) success_action_status: Option<(String, String)>,
@@ -258,14 +267,14 @@ fn conditions_to_json_array(cond: &PostPolicyCondition) -> Vec<Value> {
     }
 
     // {"x-oss-signature-version": "OSS4-HMAC-SHA256"}
-    arr.push(json!({
-        "x-oss-signature-version": &cond.x_oss_signature_version
-    }));
+    if let Some(version) = &cond.x_oss_signature_version {
+        arr.push(json!({ "x-oss-signature-version": version }));
+    }
 
     // {"x-oss-credential": "..."}
-    arr.push(json!({
-        "x-oss-credential": &cond.x_oss_credential
-    }));
+    if let Some(credential) = &cond.x_oss_credential {
+        arr.push(json!({ "x-oss-credential": credential }));
+    }
 
     // {"x-oss-security-token": "..."}
     if let Some(token) = &cond.x_oss_security_token {
@@ -275,9 +284,9 @@ fn conditions_to_json_array(cond: &PostPolicyCondition) -> Vec<Value> {
     }
 
     // {"x-oss-date": "..."}
-    arr.push(json!({
-        "x-oss-date": &cond.x_oss_date
-    }));
+    if let Some(date) = &cond.x_oss_date {
+        arr.push(json!({ "x-oss-date": date }));
+    }
 
     // ["content-length-range", 1, 10]
     if let Some((min, max)) = cond.content_length_range {
@@ -375,20 +384,67 @@ where
     arr.serialize(serializer)
 }
 
+/// 表单上传需要的字段，V1和V4签名使用的表单字段不同，可以使用[`GeneratePolicyResult::form_fields`]获取
 #[derive(Debug)]
 pub struct GeneratePolicyResult {
-    /// 固定为`OSS4-HMAC-SHA256`
-    pub x_oss_signature_version: String,
-    pub x_oss_credential: String,
-    pub x_oss_date: String,
+    /// 表单提交（POST）的地址，按照client的寻址方式生成
+    pub url: String,
+    /// 生成policy时client使用的签名版本
+    pub signature_version: SignatureVersion,
+    /// V4：固定为`OSS4-HMAC-SHA256`
+    pub x_oss_signature_version: Option<String>,
+    /// V4
+    pub x_oss_credential: Option<String>,
+    /// V4
+    pub x_oss_date: Option<String>,
+    /// V1：表单字段为`OSSAccessKeyId`
+    pub oss_access_key_id: Option<String>,
+    /// 签名，V4的表单字段为`x-oss-signature`，V1为`Signature`
     pub x_oss_signature: String,
     /// base64编码后的policy字符串
     pub policy: String,
     /// base64编码后的callback字符串
     pub callback: Option<String>,
     pub callback_var: Option<HashMap<String, String>>,
-    /// 如果使用了STS临时密钥，需要在请求头中添加`x-oss-security-token`
-    pub x_oss_security_token: Option<String>,
+    /// 如果使用了STS临时密钥，需要在请求头中添加`x-oss-security-token`，`Debug`输出时显示为`***`
+    pub x_oss_security_token: Option<Secret>,
+}
+
+impl GeneratePolicyResult {
+    /// 返回需要添加到表单中的签名相关字段（表单字段名, 值），不包含`file`和policy中限制的其它字段
+    pub fn form_fields(&self) -> Vec<(&str, &str)> {
+        let mut fields = vec![("policy", self.policy.as_str())];
+        match self.signature_version {
+            SignatureVersion::V1 => {
+                if let Some(id) = &self.oss_access_key_id {
+                    fields.push(("OSSAccessKeyId", id));
+                }
+                fields.push(("Signature", &self.x_oss_signature));
+            }
+            SignatureVersion::V4 => {
+                if let Some(version) = &self.x_oss_signature_version {
+                    fields.push(("x-oss-signature-version", version));
+                }
+                if let Some(credential) = &self.x_oss_credential {
+                    fields.push(("x-oss-credential", credential));
+                }
+                if let Some(date) = &self.x_oss_date {
+                    fields.push(("x-oss-date", date));
+                }
+                fields.push(("x-oss-signature", &self.x_oss_signature));
+            }
+        }
+        if let Some(token) = &self.x_oss_security_token {
+            fields.push(("x-oss-security-token", token.expose_secret()));
+        }
+        if let Some(callback) = &self.callback {
+            fields.push(("callback", callback));
+        }
+        if let Some(vars) = &self.callback_var {
+            fields.extend(vars.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        }
+        fields
+    }
 }
 
 // endregion
@@ -448,6 +504,12 @@ pub struct GetObject<'a> {
     pub(crate) response_cache_control: Option<&'a str>,
     pub(crate) response_content_disposition: Option<&'a str>,
     pub(crate) response_content_encoding: Option<&'a str>,
+
+    /// 本次操作的总超时，包括重试和读取响应的时间，
+    /// 对`receive_bytes_stream`只包括获取响应头的时间，对`generate_presigned_url`无效
+    pub(crate) timeout: Option<Duration>,
+    /// `receive_bytes_stream`返回的流两次收到数据之间的最大间隔，不设置时使用client的`read_timeout`
+    pub(crate) idle_timeout: Option<Duration>,
 }
 
 impl GetObject<'_> {
@@ -473,22 +535,6 @@ impl GetObject<'_> {
     }
 }
 
-#[serde_as]
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "kebab-case")]
-pub struct GetObjectResponseHeader {
-    #[serde(default, skip_deserializing)]
-    pub custom_x_oss_meta: HashMap<String, String>,
-    pub x_oss_server_side_encryption: Option<String>,
-    pub x_oss_tagging_count: Option<String>,
-    pub x_oss_expiration: Option<String>,
-    #[serde_as(as = "DisplayFromStr")]
-    pub content_length: u64,
-    pub accept_ranges: Option<String>,
-    pub content_type: String,
-    pub last_modified: String,
-    pub etag: String,
-}
 // endregion: --- get object
 
 // region:    --- copy object
@@ -576,6 +622,9 @@ pub struct AppendObject<'a> {
     // 公共请求头
     content_type: Option<&'a str>,
     // content_length  自动添加
+    /// 不设置时使用client的[`PayloadSigning`]
+    #[serde(skip_serializing)]
+    pub(crate) payload_signing: Option<PayloadSigning>,
 }
 
 impl<'a, S: append_object_builder::State> OssMetaExt<'a> for AppendObjectBuilder<'a, S> {
@@ -656,52 +705,230 @@ pub struct HeadObject<'a> {
     pub if_none_match: Option<&'a str>,
 }
 
-#[serde_as]
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "kebab-case")]
-pub struct HeadObjectResponseHeader {
-    #[serde(default, skip_deserializing)]
+// endregion: --- head object
+
+// region:    --- object meta
+/// `GetObject`、`HeadObject`和`GetObjectMeta`共用的响应头
+///
+/// 不同的API返回的响应头不完全一样，比如`GetObjectMeta`只返回少量字段，
+/// 所以除了`etag`和`last_modified`外，其余字段都为`Option`
+#[derive(Debug, Clone)]
+pub struct ObjectMeta {
+    /// `x-oss-meta-*`，key不包含`x-oss-meta-`前缀，value按UTF-8解码，无效的字节替换为`U+FFFD`
     pub custom_x_oss_meta: HashMap<String, String>,
+    /// `GetObject`使用gzip压缩或chunked传输时不会返回`content-length`
+    pub content_length: Option<u64>,
+    pub etag: String,
+    pub last_modified: OffsetDateTime,
+    pub content_type: Option<String>,
+    pub content_md5: Option<String>,
+    pub accept_ranges: Option<String>,
+    pub cache_control: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_encoding: Option<String>,
+    pub expires: Option<String>,
+    pub x_oss_request_id: Option<String>,
+    pub x_oss_object_type: Option<String>,
+    pub x_oss_storage_class: Option<String>,
+    pub x_oss_hash_crc64ecma: Option<u64>,
+    pub x_oss_next_append_position: Option<u64>,
+    pub x_oss_version_id: Option<String>,
     pub x_oss_server_side_encryption: Option<String>,
+    pub x_oss_server_side_data_encryption: Option<String>,
     pub x_oss_server_side_encryption_key_id: Option<String>,
-    pub x_oss_storage_class: String,
-    pub x_oss_object_type: String,
-    pub x_oss_next_append_position: Option<String>,
-    pub x_oss_hash_crc64ecma: Option<String>,
-    pub x_oss_transition_time: Option<String>,
+    pub x_oss_restore: Option<RestoreStatus>,
+    pub x_oss_tagging_count: Option<u32>,
     pub x_oss_expiration: Option<String>,
-    pub x_oss_restore: Option<String>,
+    pub x_oss_transition_time: Option<OffsetDateTime>,
+    pub x_oss_last_access_time: Option<OffsetDateTime>,
     pub x_oss_process_status: Option<String>,
     pub x_oss_request_charged: Option<String>,
-    pub content_md5: Option<String>,
-    pub last_modified: String,
-    pub access_control_allow_origin: Option<String>,
-    pub access_control_allow_methods: Option<String>,
-    pub access_control_max_age: Option<String>,
-    pub access_control_allow_headers: Option<String>,
-    pub access_control_expose_headers: Option<String>,
-    pub x_oss_tagging_count: Option<String>,
-    pub content_type: String,
-    #[serde_as(as = "DisplayFromStr")]
-    pub content_length: u64,
-    pub etag: String,
 }
-// endregion: --- head object
 
-// region get object meta
-#[serde_as]
-#[derive(Deserialize, Debug)]
-#[serde(rename_all = "kebab-case")]
-pub struct GetObjectMetaResponseHeader {
-    #[serde_as(as = "DisplayFromStr")]
-    pub content_length: u64,
-    pub etag: String,
-    pub x_oss_transition_time: Option<String>,
-    pub x_oss_last_access_time: Option<String>,
-    pub last_modified: String,
-    pub x_oss_version_id: Option<String>,
+impl ObjectMeta {
+    pub(crate) fn from_header_map(header: &HeaderMap) -> Result<Self, Error> {
+        let mut custom_x_oss_meta = HashMap::new();
+        for (name, val) in header {
+            if let Some(key) = name.as_str().strip_prefix("x-oss-meta-") {
+                // 用户自定义的元数据可能包含中文等非ASCII字符，不能使用`to_str`
+                let val = String::from_utf8_lossy(val.as_bytes()).into_owned();
+                custom_x_oss_meta.insert(key.to_owned(), val);
+            }
+        }
+
+        Ok(Self {
+            custom_x_oss_meta,
+            content_length: parse_header(header, "content-length")?,
+            etag: required_header(header, "etag")?,
+            last_modified: parse_gmt_header(header, "last-modified")?
+                .ok_or_else(|| Error::Common("missing header: last-modified".to_owned()))?,
+            content_type: parse_header(header, "content-type")?,
+            content_md5: parse_header(header, "content-md5")?,
+            accept_ranges: parse_header(header, "accept-ranges")?,
+            cache_control: parse_header(header, "cache-control")?,
+            content_disposition: parse_header(header, "content-disposition")?,
+            content_encoding: parse_header(header, "content-encoding")?,
+            expires: parse_header(header, "expires")?,
+            x_oss_request_id: parse_header(header, "x-oss-request-id")?,
+            x_oss_object_type: parse_header(header, "x-oss-object-type")?,
+            x_oss_storage_class: parse_header(header, "x-oss-storage-class")?,
+            x_oss_hash_crc64ecma: parse_header(header, "x-oss-hash-crc64ecma")?,
+            x_oss_next_append_position: parse_header(header, "x-oss-next-append-position")?,
+            x_oss_version_id: parse_header(header, "x-oss-version-id")?,
+            x_oss_server_side_encryption: parse_header(header, "x-oss-server-side-encryption")?,
+            x_oss_server_side_data_encryption: parse_header(
+                header,
+                "x-oss-server-side-data-encryption",
+            )?,
+            x_oss_server_side_encryption_key_id: parse_header(
+                header,
+                "x-oss-server-side-encryption-key-id",
+            )?,
+            x_oss_restore: parse_header(header, "x-oss-restore")?,
+            x_oss_tagging_count: parse_header(header, "x-oss-tagging-count")?,
+            x_oss_expiration: parse_header(header, "x-oss-expiration")?,
+            x_oss_transition_time: parse_gmt_header(header, "x-oss-transition-time")?,
+            x_oss_last_access_time: parse_gmt_header(header, "x-oss-last-access-time")?,
+            x_oss_process_status: parse_header(header, "x-oss-process-status")?,
+            x_oss_request_charged: parse_header(header, "x-oss-request-charged")?,
+        })
+    }
 }
-// endregion
+
+/// `x-oss-restore`响应头，归档类型的Object提交解冻请求后才会返回
+///
+/// - 正在解冻：`ongoing-request="true"`
+/// - 解冻完成：`ongoing-request="false", expiry-date="Sun, 16 Apr 2017 08:12:33 GMT"`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestoreStatus {
+    pub ongoing_request: bool,
+    /// 解冻完成后，Object可被读取的截止时间
+    pub expiry_date: Option<OffsetDateTime>,
+}
+
+impl FromStr for RestoreStatus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::Common(format!("invalid x-oss-restore: {}", s));
+
+        let mut ongoing_request = None;
+        let mut expiry_date = None;
+        // expiry-date的值中也包含`,`，所以按照`key="value"`的形式逐个解析，而不是直接split(',')
+        let mut rest = s.trim();
+        while !rest.is_empty() {
+            let (key, after_key) = rest.split_once('=').ok_or_else(invalid)?;
+            let after_quote = after_key.strip_prefix('"').ok_or_else(invalid)?;
+            let (value, after_value) = after_quote.split_once('"').ok_or_else(invalid)?;
+            match key.trim() {
+                "ongoing-request" => ongoing_request = Some(value == "true"),
+                "expiry-date" => {
+                    expiry_date = Some(parse_gmt(value).map_err(|_| invalid())?);
+                }
+                _ => {}
+            }
+            rest = after_value.trim_start_matches([',', ' ']);
+        }
+
+        Ok(Self {
+            ongoing_request: ongoing_request.ok_or_else(invalid)?,
+            expiry_date,
+        })
+    }
+}
+
+#[test]
+fn object_meta_from_header_map_test() {
+    use reqwest::header::HeaderValue;
+
+    let mut header = HeaderMap::new();
+    header.insert("content-length", HeaderValue::from_static("344606"));
+    header.insert(
+        "etag",
+        HeaderValue::from_static("\"5B3C1A2E053D763E1B002CC607C5A0FE\""),
+    );
+    header.insert(
+        "last-modified",
+        HeaderValue::from_static("Fri, 24 Feb 2012 06:07:48 GMT"),
+    );
+    header.insert("content-type", HeaderValue::from_static("image/jpg"));
+    header.insert("x-oss-object-type", HeaderValue::from_static("Normal"));
+    header.insert("x-oss-storage-class", HeaderValue::from_static("Archive"));
+    header.insert(
+        "x-oss-hash-crc64ecma",
+        HeaderValue::from_static("12032336538391452345"),
+    );
+    header.insert("x-oss-tagging-count", HeaderValue::from_static("2"));
+    header.insert(
+        "x-oss-restore",
+        HeaderValue::from_static(
+            "ongoing-request=\"false\", expiry-date=\"Sun, 16 Apr 2017 08:12:33 GMT\"",
+        ),
+    );
+    header.insert("x-oss-meta-author", HeaderValue::from_static("alice"));
+
+    let meta = ObjectMeta::from_header_map(&header).unwrap();
+    assert_eq!(meta.content_length, Some(344606));
+    assert_eq!(meta.last_modified.unix_timestamp(), 1330063668);
+    assert_eq!(meta.x_oss_hash_crc64ecma, Some(12032336538391452345));
+    assert_eq!(meta.x_oss_tagging_count, Some(2));
+    assert_eq!(meta.x_oss_storage_class.as_deref(), Some("Archive"));
+    let restore = meta.x_oss_restore.unwrap();
+    assert!(!restore.ongoing_request);
+    assert_eq!(restore.expiry_date.unwrap().unix_timestamp(), 1492330353);
+    assert_eq!(meta.custom_x_oss_meta.get("author").unwrap(), "alice");
+
+    // 格式错误的header返回错误而不是panic
+    header.insert("content-length", HeaderValue::from_static("abc"));
+    assert!(ObjectMeta::from_header_map(&header).is_err());
+    header.insert("content-length", HeaderValue::from_static("344606"));
+    header.insert("last-modified", HeaderValue::from_static("yesterday"));
+    assert!(ObjectMeta::from_header_map(&header).is_err());
+}
+
+#[test]
+fn object_meta_without_content_length_test() {
+    use reqwest::header::HeaderValue;
+
+    // 开启gzip压缩或chunked传输的GetObject响应
+    let mut header = HeaderMap::new();
+    header.insert("etag", HeaderValue::from_static("\"5B3C1A2E\""));
+    header.insert(
+        "last-modified",
+        HeaderValue::from_static("Fri, 24 Feb 2012 06:07:48 GMT"),
+    );
+    header.insert("content-encoding", HeaderValue::from_static("gzip"));
+    header.insert("transfer-encoding", HeaderValue::from_static("chunked"));
+
+    let meta = ObjectMeta::from_header_map(&header).unwrap();
+    assert_eq!(meta.content_length, None);
+    assert_eq!(meta.content_encoding.as_deref(), Some("gzip"));
+}
+
+#[test]
+fn object_meta_utf8_custom_meta_test() {
+    use reqwest::header::HeaderValue;
+
+    let mut header = HeaderMap::new();
+    header.insert("etag", HeaderValue::from_static("\"5B3C1A2E\""));
+    header.insert(
+        "last-modified",
+        HeaderValue::from_static("Fri, 24 Feb 2012 06:07:48 GMT"),
+    );
+    header.insert(
+        "x-oss-meta-author",
+        HeaderValue::from_bytes("张三".as_bytes()).unwrap(),
+    );
+    header.insert(
+        "x-oss-meta-invalid",
+        HeaderValue::from_bytes(b"a\xffb").unwrap(),
+    );
+
+    let meta = ObjectMeta::from_header_map(&header).unwrap();
+    assert_eq!(meta.custom_x_oss_meta["author"], "张三");
+    assert_eq!(meta.custom_x_oss_meta["invalid"], "a\u{fffd}b");
+}
+// endregion: --- object meta
 
 // region callback
 
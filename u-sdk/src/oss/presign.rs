@@ -0,0 +1,204 @@
+//! 通用的预签名URL
+//!
+//! [在URL中包含签名](https://help.aliyun.com/zh/oss/developer-reference/add-signatures-to-urls)
+//!
+//! 除了`PutObject`、`GetObject`、`HeadObject`自带的`generate_presigned_url`外，
+//! 可以使用[`Presign`]为任意API生成预签名URL，如浏览器直传分片时为每个`UploadPart`生成URL
+
+use super::sign_v4::HTTPVerb;
+use super::utils::validate_object_name;
+use super::{Client, Error};
+use bon::Builder;
+use std::collections::HashMap;
+use url::Url;
+
+/// 为任意API生成预签名URL
+///
+/// - `header`：需要参与签名的header，使用URL的一方发起请求时必须携带相同的header
+/// - `query`：需要参与签名的query参数，如`UploadPart`的`partNumber`和`uploadId`
+/// - `object_name`：为`None`时生成bucket级别的URL
+/// - `expires`：URL有效期，单位秒。使用长期AccessKey时最大为604800秒（7天），使用STS临时凭证时最大为43200秒（12小时）
+#[derive(Builder)]
+pub struct Presign<'a> {
+    #[builder(start_fn)]
+    client: &'a Client,
+    #[builder(field)]
+    headers: HashMap<String, String>,
+    #[builder(field)]
+    queries: Vec<(String, String)>,
+    method: HTTPVerb,
+    object_name: Option<&'a str>,
+    expires: i32,
+}
+
+impl<S: presign_builder::State> PresignBuilder<'_, S> {
+    pub fn header(mut self, key: impl Into<String>, val: impl Into<String>) -> Self {
+        self.headers.insert(key.into(), val.into());
+        self
+    }
+
+    pub fn headers<K: Into<String>, V: Into<String>>(
+        mut self,
+        headers: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        self.headers
+            .extend(headers.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
+    /// 值为空字符串时，query只有key，如`?uploads`
+    pub fn query(mut self, key: impl Into<String>, val: impl Into<String>) -> Self {
+        self.queries.push((key.into(), val.into()));
+        self
+    }
+
+    pub fn queries<K: Into<String>, V: Into<String>>(
+        mut self,
+        queries: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        self.queries
+            .extend(queries.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+}
+
+impl Presign<'_> {
+    pub async fn generate_presigned_url(&self) -> Result<String, Error> {
+        let client = self.client;
+        let mut url = match self.object_name {
+            Some(object_name) => {
+                validate_object_name(object_name)?;
+                client.object_url(&client.bucket, object_name)?
+            }
+            None => client.bucket_url(&client.bucket)?,
+        };
+        if !self.queries.is_empty() {
+            url.query_pairs_mut().extend_pairs(&self.queries);
+        }
+
+        client
+            .presigned_url(self.method, url, self.headers.clone(), self.expires)
+            .await
+    }
+}
+
+impl Client {
+    pub fn presign(&self) -> PresignBuilder<'_> {
+        Presign::builder(self)
+    }
+
+    /// 生成`UploadPart`的预签名URL，使用PUT方法上传分片
+    ///
+    /// - `upload_id`：`InitiateMultipartUpload`返回的UploadId
+    /// - `part_number`：分片号，取值范围1~10000
+    pub async fn presign_upload_part(
+        &self,
+        object_name: &str,
+        upload_id: &str,
+        part_number: u16,
+        expires: i32,
+    ) -> Result<String, Error> {
+        if !(1..=10000).contains(&part_number) {
+            return Err(Error::Common(
+                "part_number must be between 1 and 10000".to_owned(),
+            ));
+        }
+        self.presign()
+            .method(HTTPVerb::Put)
+            .object_name(object_name)
+            .query("partNumber", part_number.to_string())
+            .query("uploadId", upload_id)
+            .expires(expires)
+            .build()
+            .generate_presigned_url()
+            .await
+    }
+
+    /// 生成`DeleteObject`的预签名URL，使用DELETE方法
+    pub async fn presign_delete_object(
+        &self,
+        object_name: &str,
+        expires: i32,
+    ) -> Result<String, Error> {
+        self.presign()
+            .method(HTTPVerb::Delete)
+            .object_name(object_name)
+            .expires(expires)
+            .build()
+            .generate_presigned_url()
+            .await
+    }
+
+    /// 所有预签名URL的公共部分：使用client的凭证和bucket签名
+    ///
+    /// `url`中需要已经包含了所有参与签名的query参数
+    pub(crate) async fn presigned_url(
+        &self,
+        http_verb: HTTPVerb,
+        url: Url,
+        header_map: HashMap<String, String>,
+        expires: i32,
+    ) -> Result<String, Error> {
+        let creds = self.load_credentials().await?;
+        self.signer().presign_url(
+            &creds,
+            http_verb,
+            url,
+            header_map,
+            self.signing_bucket(&self.bucket),
+            expires,
+        )
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn presign_upload_part_test() {
+    use crate::credentials::{Credentials, StaticCredentialsProvider};
+    use std::sync::Arc;
+
+    let client = Client::builder()
+        .credentials_provider(Arc::new(StaticCredentialsProvider::new(Credentials::new(
+            "id",
+            "secret",
+            Some("token".to_owned()),
+            None,
+        ))))
+        .endpoint("oss-cn-hangzhou.aliyuncs.com")
+        .region("cn-hangzhou")
+        .bucket("example")
+        .build();
+
+    let url = client
+        .presign_upload_part("a/b.bin", "0004B9895DBBB6EC98E", 3, 300)
+        .await
+        .unwrap();
+    let url = Url::parse(&url).unwrap();
+    assert_eq!(url.path(), "/a/b.bin");
+    let queries = url.query_pairs().into_owned().collect::<HashMap<_, _>>();
+    assert_eq!(queries["partNumber"], "3");
+    assert_eq!(queries["uploadId"], "0004B9895DBBB6EC98E");
+    assert_eq!(queries["x-oss-security-token"], "token");
+    assert_eq!(queries["x-oss-expires"], "300");
+    assert!(queries.contains_key("x-oss-signature"));
+
+    assert!(
+        client
+            .presign_upload_part("a/b.bin", "id", 0, 300)
+            .await
+            .is_err()
+    );
+
+    let client = Client {
+        signature_version: super::SignatureVersion::V1,
+        ..client
+    };
+    let url = client.presign_delete_object("a/b.bin", 300).await.unwrap();
+    let url = Url::parse(&url).unwrap();
+    let queries = url.query_pairs().into_owned().collect::<HashMap<_, _>>();
+    assert_eq!(queries["OSSAccessKeyId"], "id");
+    assert_eq!(queries["security-token"], "token");
+    assert!(queries.contains_key("Expires"));
+    assert!(queries.contains_key("Signature"));
+    assert!(!queries.contains_key("x-oss-signature"));
+}
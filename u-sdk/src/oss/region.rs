@@ -1,13 +1,15 @@
 //! [API 文档](https://help.aliyun.com/zh/oss/developer-reference/describeregions)
+//!
+//! 同时提供根据bucket自动获取region和endpoint的方法：[`Client::discover_endpoint`]，
+//! 以及在构建client时自动获取的[`ClientBuilder::build_with_discovery`]
 
-use super::Client;
-use super::utils::{get_request_header, parse_xml_response};
+use super::utils::{SignedBody, parse_xml_response};
+use super::{Client, ClientBuilder, EndpointType, client_builder};
 use crate::oss::Error;
 use crate::oss::sign_v4::HTTPVerb;
-use bon::Builder;
+use bon::{Builder, bon};
 use serde::Deserialize;
 use std::collections::HashMap;
-use url::Url;
 
 #[derive(Builder)]
 pub struct DescribeRegions<'a> {
@@ -36,33 +38,22 @@ impl DescribeRegions<'_> {
     pub async fn send(&self) -> Result<RegionInfoList, Error> {
         let client = self.client;
 
-        let request_url = Url::parse_with_params(
-            &format!("https://{}", client.endpoint),
-            [("regions", self.region.unwrap_or_default())],
-        )
-        .unwrap();
-
-        let creds = client.credentials_provider.load().await?;
-        let mut req_header_map = HashMap::new();
-        if let Some(token) = &creds.sts_security_token {
-            req_header_map.insert("x-oss-security-token".to_string(), token.clone());
-        }
+        let mut request_url = client.service_url()?;
+        request_url
+            .query_pairs_mut()
+            .append_pair("regions", self.region.unwrap_or_default());
 
-        let header_map = get_request_header(
-            &creds.access_key_id,
-            &creds.access_key_secret,
-            req_header_map,
-            &request_url,
-            HTTPVerb::Get,
-            &client.region,
-            None,
-        );
+        let creds = client.load_credentials().await?;
 
         let resp = client
-            .http_client
-            .get(request_url)
-            .headers(header_map)
-            .send()
+            .send_signed(
+                &creds,
+                HTTPVerb::Get,
+                request_url,
+                HashMap::new(),
+                None,
+                SignedBody::Empty,
+            )
             .await?;
 
         let res = parse_xml_response(resp).await?;
@@ -75,3 +66,193 @@ impl Client {
         DescribeRegions::builder(self)
     }
 }
+
+#[bon]
+impl Client {
+    /// 通过`GetBucketLocation`和`DescribeRegions`获取bucket所在的region和endpoint，返回配置好的新client
+    ///
+    /// 使用时可以先用任意可用的region和endpoint构建client，再调用此方法，如：
+    ///
+    /// ```ignore
+    /// let client = oss::Client::builder()
+    ///     .credentials_provider(provider)
+    ///     .endpoint("oss-cn-hangzhou.aliyuncs.com")
+    ///     .region("cn-hangzhou")
+    ///     .bucket("example-bucket")
+    ///     .build()
+    ///     .discover_endpoint()
+    ///     .accelerate_if_enabled(true)
+    ///     .send()
+    ///     .await?;
+    /// ```
+    ///
+    /// - `endpoint_type`：使用外网、内网还是传输加速的endpoint，默认为外网
+    /// - `accelerate_if_enabled`：为`true`时，如果`GetBucketInfo`返回bucket已开启传输加速，则使用传输加速的endpoint
+    ///
+    /// 返回的client除了region和endpoint外，其它配置与当前client相同
+    #[builder(finish_fn = send)]
+    pub async fn discover_endpoint(
+        &self,
+        #[builder(default)] endpoint_type: EndpointType,
+        #[builder(default)] accelerate_if_enabled: bool,
+    ) -> Result<Client, Error> {
+        // 返回值形如：oss-cn-hangzhou
+        let location = self.get_bucket_location().build().send().await?;
+        let region_info = self
+            .describe_regions()
+            .region(&location)
+            .build()
+            .send()
+            .await?
+            .region_info
+            .and_then(|list| list.into_iter().find(|info| info.region == location))
+            .ok_or_else(|| Error::Common(format!("region info not found: {}", location)))?;
+
+        // V4签名使用的region不带`oss-`前缀
+        let region = location
+            .strip_prefix("oss-")
+            .unwrap_or(&location)
+            .to_owned();
+        let endpoint = match endpoint_type {
+            EndpointType::Public => region_info.internet_endpoint,
+            EndpointType::Internal => region_info.internal_endpoint,
+            EndpointType::Accelerate => region_info.accelerate_endpoint.clone(),
+        };
+        let mut client = Client {
+            region,
+            endpoint,
+            ..self.clone()
+        };
+
+        if accelerate_if_enabled && endpoint_type != EndpointType::Accelerate {
+            let info = client.get_bucket_info().build().send().await?;
+            if info.bucket.transfer_acceleration == "Enabled" {
+                client.endpoint = region_info.accelerate_endpoint;
+            }
+        }
+
+        Ok(client)
+    }
+}
+
+impl<S: client_builder::IsComplete> ClientBuilder<S> {
+    /// 构建client，并通过[`Client::discover_endpoint`]获取bucket所在的region和endpoint
+    ///
+    /// 构建时设置的`endpoint`和`region`只用于发送`GetBucketLocation`和`DescribeRegions`请求，
+    /// 可以是任意可用的region，不需要与bucket所在的region一致：
+    ///
+    /// ```ignore
+    /// let client = oss::Client::builder()
+    ///     .credentials_provider(provider)
+    ///     .endpoint("oss-cn-hangzhou.aliyuncs.com")
+    ///     .region("cn-hangzhou")
+    ///     .bucket("example-bucket")
+    ///     .build_with_discovery(EndpointType::Public, true)
+    ///     .await?;
+    /// ```
+    ///
+    /// - `endpoint_type`：使用外网、内网还是传输加速的endpoint
+    /// - `accelerate_if_enabled`：为`true`时，如果bucket已开启传输加速，则使用传输加速的endpoint
+    pub async fn build_with_discovery(
+        self,
+        endpoint_type: EndpointType,
+        accelerate_if_enabled: bool,
+    ) -> Result<Client, Error> {
+        let client = self.build();
+        client
+            .discover_endpoint()
+            .endpoint_type(endpoint_type)
+            .accelerate_if_enabled(accelerate_if_enabled)
+            .send()
+            .await
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn build_with_discovery_test() {
+    use crate::credentials::{Credentials, StaticCredentialsProvider};
+    use std::sync::Arc;
+    use u_sdk_common::transport::HttpTransport;
+
+    // 根据请求返回固定的响应，bucket在cn-shanghai，并开启了传输加速
+    struct MockTransport;
+    #[async_trait::async_trait]
+    impl HttpTransport for MockTransport {
+        async fn execute(
+            &self,
+            request: reqwest::Request,
+        ) -> Result<reqwest::Response, u_sdk_common::Error> {
+            let url = request.url();
+            let body = match (url.host_str().unwrap(), url.query().unwrap()) {
+                ("example.oss-cn-hangzhou.aliyuncs.com", "location") => {
+                    "<LocationConstraint>oss-cn-shanghai</LocationConstraint>".to_owned()
+                }
+                ("oss-cn-hangzhou.aliyuncs.com", "regions=oss-cn-shanghai") => r#"
+                    <RegionInfoList>
+                      <RegionInfo>
+                        <Region>oss-cn-shanghai</Region>
+                        <InternetEndpoint>oss-cn-shanghai.aliyuncs.com</InternetEndpoint>
+                        <InternalEndpoint>oss-cn-shanghai-internal.aliyuncs.com</InternalEndpoint>
+                        <AccelerateEndpoint>oss-accelerate.aliyuncs.com</AccelerateEndpoint>
+                      </RegionInfo>
+                    </RegionInfoList>"#
+                    .to_owned(),
+                ("example.oss-cn-shanghai.aliyuncs.com", "bucketInfo") => r#"
+                    <BucketInfo>
+                      <Bucket>
+                        <CreationDate>2013-07-31T10:56:21.000Z</CreationDate>
+                        <ExtranetEndpoint>oss-cn-shanghai.aliyuncs.com</ExtranetEndpoint>
+                        <IntranetEndpoint>oss-cn-shanghai-internal.aliyuncs.com</IntranetEndpoint>
+                        <Location>oss-cn-shanghai</Location>
+                        <StorageClass>Standard</StorageClass>
+                        <Name>example</Name>
+                        <ResourceGroupId>rg-1</ResourceGroupId>
+                        <Owner><DisplayName>username</DisplayName><ID>27183473914</ID></Owner>
+                        <AccessControlList><Grant>private</Grant></AccessControlList>
+                        <DataRedundancyType>LRS</DataRedundancyType>
+                        <CrossRegionReplication>Disabled</CrossRegionReplication>
+                        <TransferAcceleration>Enabled</TransferAcceleration>
+                        <AccessMonitor>Disabled</AccessMonitor>
+                        <BucketPolicy><LogBucket></LogBucket><LogPrefix></LogPrefix></BucketPolicy>
+                        <Comment></Comment>
+                        <ServerSideEncryptionRule><SSEAlgorithm>None</SSEAlgorithm></ServerSideEncryptionRule>
+                        <BlockPublicAccess>false</BlockPublicAccess>
+                      </Bucket>
+                    </BucketInfo>"#
+                    .to_owned(),
+                (host, query) => panic!("unexpected request: {} {}", host, query),
+            };
+            Ok(http::Response::builder()
+                .status(200)
+                .body(body)
+                .unwrap()
+                .into())
+        }
+    }
+
+    let builder = || {
+        Client::builder()
+            .credentials_provider(Arc::new(StaticCredentialsProvider::new(Credentials::new(
+                "id", "secret", None, None,
+            ))))
+            .endpoint("oss-cn-hangzhou.aliyuncs.com")
+            .region("cn-hangzhou")
+            .bucket("example")
+            .http_client(MockTransport)
+    };
+
+    let client = builder()
+        .build_with_discovery(EndpointType::Internal, false)
+        .await
+        .unwrap();
+    assert_eq!(client.region(), "cn-shanghai");
+    assert_eq!(client.endpoint(), "oss-cn-shanghai-internal.aliyuncs.com");
+
+    let client = builder()
+        .build_with_discovery(EndpointType::Public, true)
+        .await
+        .unwrap();
+    assert_eq!(client.region(), "cn-shanghai");
+    assert_eq!(client.endpoint(), "oss-accelerate.aliyuncs.com");
+}
@@ -1,12 +1,11 @@
 use super::Client;
 use super::sign_v4::HTTPVerb;
-use super::utils::{get_request_header, parse_xml_response};
+use super::utils::{SignedBody, parse_xml_response};
 use crate::oss::Error;
 use bon::Builder;
 use serde::{Deserialize, Serialize};
 use serde_with::{DisplayFromStr, serde_as};
 use std::collections::HashMap;
-use url::Url;
 
 #[serde_as]
 #[serde_with::skip_serializing_none]
@@ -87,36 +86,26 @@ impl ListBuckets<'_> {
             serde_json::from_value(serde_json::to_value(self).unwrap()).unwrap();
 
         let client = self.client;
-        // 构建url用于签名使用;签名使用的url的host在构建签名的时候用不到的，理论上可以是任意值，这里用endpoint
-        // 此api不涉及bucket，url使用https://endpoint
-        let request_url =
-            Url::parse_with_params(&format!("https://{}", client.endpoint), query_map).unwrap();
+        // 此api不涉及bucket，url使用{scheme}://endpoint/
+        let mut request_url = client.service_url()?;
+        request_url.query_pairs_mut().extend_pairs(query_map);
 
         let mut request_header_map = HashMap::new();
         if let Some(s) = self.x_oss_resource_group_id {
             request_header_map.insert("x-oss-resource-group-id".to_owned(), s.to_owned());
         }
 
-        let creds = client.credentials_provider.load().await?;
-        if let Some(token) = &creds.sts_security_token {
-            request_header_map.insert("x-oss-security-token".to_string(), token.clone());
-        }
-
-        let header_map = get_request_header(
-            &creds.access_key_id,
-            &creds.access_key_secret,
-            request_header_map,
-            &request_url,
-            HTTPVerb::Get,
-            &client.region,
-            None,
-        );
+        let creds = client.load_credentials().await?;
 
         let resp = client
-            .http_client
-            .get(request_url)
-            .headers(header_map)
-            .send()
+            .send_signed(
+                &creds,
+                HTTPVerb::Get,
+                request_url,
+                request_header_map,
+                None,
+                SignedBody::Empty,
+            )
             .await?;
 
         let res = parse_xml_response(resp).await?;
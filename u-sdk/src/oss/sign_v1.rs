@@ -0,0 +1,191 @@
+use crate::oss::sign_v4::HTTPVerb;
+use base64::{Engine, engine::general_purpose};
+use hmac::{Hmac, Mac};
+use percent_encoding::percent_decode_str;
+use sha1::Sha1;
+use std::collections::BTreeMap;
+use url::Url;
+// 签名文档：
+// - Header：https://help.aliyun.com/zh/oss/developer-reference/include-signatures-in-the-authorization-header
+// - URL：https://help.aliyun.com/zh/oss/developer-reference/ddd-signatures-to-urls
+
+// 参与签名计算的子资源，不在列表中的query不参与V1签名
+const SUB_RESOURCES: &[&str] = &[
+    "acl",
+    "append",
+    "bucketInfo",
+    "callback",
+    "callback-var",
+    "cname",
+    "comp",
+    "continuation-token",
+    "cors",
+    "delete",
+    "encryption",
+    "endTime",
+    "img",
+    "inventory",
+    "inventoryId",
+    "lifecycle",
+    "live",
+    "location",
+    "logging",
+    "objectMeta",
+    "partNumber",
+    "policy",
+    "position",
+    "qos",
+    "qosInfo",
+    "referer",
+    "regionList",
+    "regions",
+    "replication",
+    "replicationLocation",
+    "replicationProgress",
+    "requestPayment",
+    "response-cache-control",
+    "response-content-disposition",
+    "response-content-encoding",
+    "response-content-language",
+    "response-content-type",
+    "response-expires",
+    "restore",
+    "security-token",
+    "sequential",
+    "startTime",
+    "stat",
+    "status",
+    "style",
+    "styleName",
+    "symlink",
+    "tagging",
+    "transferAcceleration",
+    "uploadId",
+    "uploads",
+    "versionId",
+    "versioning",
+    "versions",
+    "website",
+    "worm",
+    "wormExtend",
+    "wormId",
+    "x-oss-process",
+];
+
+/// V1签名的CanonicalizedResource：`/{bucket}/{object}?{子资源}`
+///
+/// 与V4不同，object名称和子资源的值都不进行url编码
+fn get_canonicalized_resource(uri: &Url, bucket: Option<&str>) -> String {
+    let path = percent_decode_str(uri.path()).decode_utf8_lossy();
+    let mut resource = match bucket {
+        Some(bucket) => format!("/{}{}", bucket, path),
+        // 不涉及bucket的API，path为`/`；路径方式寻址时，path本身已经是`/{bucket}/{object}`的形式
+        None => path.into_owned(),
+    };
+
+    let sub_resources = uri
+        .query_pairs()
+        .filter(|(k, _)| SUB_RESOURCES.contains(&k.as_ref()))
+        .collect::<BTreeMap<_, _>>();
+    if !sub_resources.is_empty() {
+        let s = sub_resources
+            .into_iter()
+            .map(|(k, v)| {
+                if v.is_empty() {
+                    k.into_owned()
+                } else {
+                    format!("{}={}", k, v)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+        resource.push('?');
+        resource.push_str(&s);
+    }
+
+    resource
+}
+
+pub(crate) struct SignV1Param<'a> {
+    pub http_verb: HTTPVerb,
+    pub uri: &'a Url,
+    // 如果host中有bucket，则此处传入bucket名称，用于构建CanonicalizedResource
+    pub bucket: Option<&'a str>,
+    // key为小写
+    pub header_map: &'a BTreeMap<&'a str, &'a str>,
+    // Header签名时为`Date`，URL签名时为`Expires`
+    pub date_or_expires: &'a str,
+}
+
+/// 返回base64编码的签名
+pub(crate) fn generate_v1_signature(access_key_secret: &str, param: SignV1Param<'_>) -> String {
+    let header_map = param.header_map;
+    let content_md5 = header_map.get("content-md5").copied().unwrap_or_default();
+    let content_type = header_map.get("content-type").copied().unwrap_or_default();
+    let mut canonicalized_oss_headers = String::new();
+    for (k, v) in header_map.iter().filter(|(k, _)| k.starts_with("x-oss-")) {
+        canonicalized_oss_headers.push_str(&format!("{}:{}\n", k, v.trim()));
+    }
+
+    let string_to_sign = format!(
+        "{}\n{}\n{}\n{}\n{}{}",
+        param.http_verb,
+        content_md5,
+        content_type,
+        param.date_or_expires,
+        canonicalized_oss_headers,
+        get_canonicalized_resource(param.uri, param.bucket)
+    );
+
+    let mut mac = Hmac::<Sha1>::new_from_slice(access_key_secret.as_bytes()).unwrap();
+    mac.update(string_to_sign.as_bytes());
+    general_purpose::STANDARD.encode(mac.finalize().into_bytes())
+}
+
+/// PostObject的V1签名：对base64编码后的policy进行HMAC-SHA1
+pub(crate) fn generate_v1_policy_signature(
+    access_key_secret: &str,
+    encoded_policy: &str,
+) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(access_key_secret.as_bytes()).unwrap();
+    mac.update(encoded_policy.as_bytes());
+    general_purpose::STANDARD.encode(mac.finalize().into_bytes())
+}
+
+#[test]
+fn sign_v1_test() {
+    // 使用文档示例中的参数，host等非x-oss-*的header不参与签名
+    let url = Url::parse("https://oss-example.oss-cn-hangzhou.aliyuncs.com/nelson").unwrap();
+    let mut header_map = BTreeMap::new();
+    header_map.insert(
+        "content-md5",
+        "ODBGOERFMDMzQTczRUY3NUE3NzA5QzdFNUYzMDQxNEM=",
+    );
+    header_map.insert("content-type", "text/html");
+    header_map.insert("x-oss-magic", "abracadabra");
+    header_map.insert("x-oss-meta-author", "foo@example.com");
+    header_map.insert("host", "oss-example.oss-cn-hangzhou.aliyuncs.com");
+    let param = SignV1Param {
+        http_verb: HTTPVerb::Put,
+        uri: &url,
+        bucket: Some("oss-example"),
+        header_map: &header_map,
+        date_or_expires: "Thu, 17 Nov 2005 18:49:58 GMT",
+    };
+    let signature = generate_v1_signature("OtxrzxIsfpFjA7SwPzILwy8Bw21TLhquhboDYROV", param);
+    assert_eq!(signature, "fV5fq7DPwNbrrig7nvUSZIVyruI=");
+
+    let url = Url::parse("https://example.oss-cn-hangzhou.aliyuncs.com/a.txt?acl&foo=bar").unwrap();
+    assert_eq!(
+        get_canonicalized_resource(&url, Some("example")),
+        "/example/a.txt?acl"
+    );
+    let url = Url::parse(
+        "https://oss-cn-hangzhou.aliyuncs.com/example/%E6%B5%8B.txt?uploadId=1&partNumber=2",
+    )
+    .unwrap();
+    assert_eq!(
+        get_canonicalized_resource(&url, None),
+        "/example/测.txt?partNumber=2&uploadId=1"
+    );
+}
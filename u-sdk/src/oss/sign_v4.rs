@@ -9,7 +9,9 @@ use u_sdk_common::helper::gmt_format;
 use url::Url;
 // 签名文档：https://help.aliyun.com/zh/oss/developer-reference/recommend-to-use-signature-version-4
 
-pub(crate) enum HTTPVerb {
+/// 请求使用的HTTP方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HTTPVerb {
     Get,
     Put,
     Post,
@@ -28,6 +30,53 @@ impl Display for HTTPVerb {
     }
 }
 
+/// V4签名时请求体（payload）的签名方式
+///
+/// 可以在[`Client`](super::Client)上统一设置，也可以在`PutObject`、`AppendObject`上单独设置，
+/// 只对V4签名有效
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PayloadSigning {
+    /// 请求体不参与签名，`x-oss-content-sha256`为`UNSIGNED-PAYLOAD`
+    #[default]
+    Unsigned,
+    /// 计算请求体的SHA-256并参与签名，服务端会校验请求体和签名是否一致
+    ///
+    /// 上传文件时需要额外读取一遍文件计算哈希值（和`Content-MD5`在同一次读取中完成）
+    Signed,
+}
+
+pub(crate) const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+impl From<HTTPVerb> for reqwest::Method {
+    fn from(verb: HTTPVerb) -> Self {
+        match verb {
+            HTTPVerb::Get => reqwest::Method::GET,
+            HTTPVerb::Put => reqwest::Method::PUT,
+            HTTPVerb::Post => reqwest::Method::POST,
+            HTTPVerb::Delete => reqwest::Method::DELETE,
+            HTTPVerb::Head => reqwest::Method::HEAD,
+        }
+    }
+}
+
+impl TryFrom<&reqwest::Method> for HTTPVerb {
+    type Error = super::Error;
+
+    fn try_from(method: &reqwest::Method) -> Result<Self, Self::Error> {
+        match method.as_str() {
+            "GET" => Ok(HTTPVerb::Get),
+            "PUT" => Ok(HTTPVerb::Put),
+            "POST" => Ok(HTTPVerb::Post),
+            "DELETE" => Ok(HTTPVerb::Delete),
+            "HEAD" => Ok(HTTPVerb::Head),
+            other => Err(super::Error::Common(format!(
+                "unsupported http method: {}",
+                other
+            ))),
+        }
+    }
+}
+
 // 构造过程和要求参考api文档中的[步骤1：构造CanonicalRequest]部分
 // 对于canonical_header的构造，对[k:v]有一些要求（如必须存在的header，存在则加入签名的header，等等）
 // 这里会保证k是小写的，其它要求不会进行检查，由调用者保证
@@ -44,7 +93,8 @@ fn get_canonical_request(
         s.push_str(uri.path()); // uri.path即为object的名称，同时，要求path不能以/结尾
         s
     } else {
-        "/".to_owned()
+        // 不涉及bucket的API，path为`/`；路径方式寻址时，path本身已经是`/{bucket}/{object}`的形式
+        uri.path().to_owned()
     };
     let canonical_query_string = uri
         .query_pairs()
@@ -84,6 +134,11 @@ fn get_canonical_request(
         "".to_owned()
     };
 
+    // HashedPayload和header中的x-oss-content-sha256保持一致，预签名URL中没有该header，使用UNSIGNED-PAYLOAD
+    let hashed_payload = canonical_header
+        .get("x-oss-content-sha256")
+        .copied()
+        .unwrap_or(UNSIGNED_PAYLOAD);
     let res = format!(
         "{}\n{}\n{}\n{}\n{}\n{}",
         http_verb,
@@ -91,7 +146,7 @@ fn get_canonical_request(
         canonical_query_string,
         canonical_headers_str,
         additional_header_str,
-        "UNSIGNED-PAYLOAD"
+        hashed_payload
     );
 
     res
@@ -219,3 +274,34 @@ pub(crate) fn generate_v4_signature(
         string_to_sign.as_bytes(),
     ))
 }
+
+#[test]
+fn canonical_uri_addressing_style_test() {
+    let header = BTreeMap::new();
+    let virtual_hosted =
+        Url::parse("https://example.oss-cn-hangzhou.aliyuncs.com/a/b.txt").unwrap();
+    let path_style = Url::parse("https://oss-cn-hangzhou.aliyuncs.com/example/a/b.txt").unwrap();
+    let s1 = get_canonical_request(
+        HTTPVerb::Get,
+        &virtual_hosted,
+        Some("example"),
+        &header,
+        None,
+    );
+    let s2 = get_canonical_request(HTTPVerb::Get, &path_style, None, &header, None);
+    assert_eq!(s1, s2);
+    assert!(s1.starts_with("GET\n/example/a/b.txt\n"));
+}
+
+#[test]
+fn canonical_request_payload_test() {
+    let url = Url::parse("https://example.oss-cn-hangzhou.aliyuncs.com/a.txt").unwrap();
+    let mut header = BTreeMap::new();
+    let s = get_canonical_request(HTTPVerb::Put, &url, Some("example"), &header, None);
+    assert!(s.ends_with("\nUNSIGNED-PAYLOAD"));
+
+    let hash = hex::encode(Sha256::digest(b"hello"));
+    header.insert("x-oss-content-sha256", hash.as_str());
+    let s = get_canonical_request(HTTPVerb::Put, &url, Some("example"), &header, None);
+    assert!(s.ends_with(&format!("\n{}", hash)));
+}
@@ -0,0 +1,237 @@
+//! 独立的OSS请求签名器
+//!
+//! 当sdk还没有封装某个OSS API时，可以自己构造请求，再使用[`Signer`]签名后发送：
+//!
+//! ```ignore
+//! let signer = client.signer();
+//! let creds = creds_provider.load().await?;
+//! let mut req = http_client
+//!     .get("https://example.oss-cn-hangzhou.aliyuncs.com/?worm")
+//!     .build()?;
+//! signer.sign_request(&creds, &mut req, Some("example"))?;
+//! let resp = http_client.execute(req).await?;
+//! ```
+
+use super::sign_v4::HTTPVerb;
+use super::utils::{
+    PresignParams, generate_presigned_url, generate_v1_presigned_url, get_request_header,
+    get_v1_request_header,
+};
+use super::{Client, Error, SignatureVersion};
+use crate::credentials::Credentials;
+use bon::bon;
+use reqwest::Method;
+use reqwest::header::HeaderMap;
+use std::collections::HashMap;
+use u_sdk_common::clock::ClockOffset;
+use url::Url;
+
+/// OSS请求签名器，支持V1和V4签名
+///
+/// `bucket`参数：
+/// - 虚拟主机方式和自定义域名方式的url中没有bucket，需要传入bucket名称用于构建签名的`/{bucket}/{object}`
+/// - 路径方式的url或者不涉及bucket的API（如`ListBuckets`）传入`None`，直接使用url的path
+#[derive(Debug, Clone)]
+pub struct Signer {
+    region: String,
+    signature_version: SignatureVersion,
+    clock_offset: ClockOffset,
+}
+
+#[bon]
+impl Signer {
+    /// - `region`：如`cn-hangzhou`，V1签名不使用
+    /// - `signature_version`：默认为[`SignatureVersion::V4`]
+    /// - `clock_offset`：签名使用的时钟偏移，默认不偏移
+    #[builder(on(String, into))]
+    pub fn new(
+        region: String,
+        #[builder(default)] signature_version: SignatureVersion,
+        #[builder(default)] clock_offset: ClockOffset,
+    ) -> Self {
+        Self {
+            region,
+            signature_version,
+            clock_offset,
+        }
+    }
+}
+
+impl Signer {
+    /// 计算签名，返回签名后完整的请求头（包含传入的`headers`）
+    ///
+    /// 使用STS临时凭证时会自动添加`x-oss-security-token`
+    pub fn sign_headers(
+        &self,
+        creds: &Credentials,
+        method: HTTPVerb,
+        url: &Url,
+        headers: HashMap<String, String>,
+        bucket: Option<&str>,
+    ) -> Result<HeaderMap, Error> {
+        match self.signature_version {
+            SignatureVersion::V1 => get_v1_request_header(
+                creds,
+                headers,
+                url,
+                method,
+                bucket,
+                &self.clock_offset.now(),
+            ),
+            SignatureVersion::V4 => get_request_header(
+                creds,
+                headers,
+                url,
+                method,
+                &self.region,
+                bucket,
+                &self.clock_offset.now(),
+            ),
+        }
+    }
+
+    /// 生成预签名URL
+    ///
+    /// - `url`：需要包含所有参与签名的query参数
+    /// - `headers`：参与签名的header，使用URL发起请求时需要携带相同的header
+    /// - `expires`：URL有效期，单位秒
+    pub fn presign_url(
+        &self,
+        creds: &Credentials,
+        method: HTTPVerb,
+        mut url: Url,
+        headers: HashMap<String, String>,
+        bucket: Option<&str>,
+        expires: i32,
+    ) -> Result<String, Error> {
+        if let Some(token) = &creds.sts_security_token {
+            let key = match self.signature_version {
+                SignatureVersion::V1 => "security-token",
+                SignatureVersion::V4 => "x-oss-security-token",
+            };
+            url.query_pairs_mut()
+                .append_pair(key, token.expose_secret());
+        }
+        let params = PresignParams {
+            access_key_id: &creds.access_key_id,
+            access_key_secret: creds.access_key_secret.expose_secret(),
+            header_map: headers,
+            presigned_url: url,
+            http_verb: method,
+            url_expires: expires,
+            bucket,
+            signing_region: &self.region,
+            now: self.clock_offset.now(),
+        };
+        match self.signature_version {
+            SignatureVersion::V1 => generate_v1_presigned_url(params),
+            SignatureVersion::V4 => generate_presigned_url(params),
+        }
+    }
+
+    /// 对`reqwest::Request`签名，签名相关的header会直接添加到请求中
+    ///
+    /// 请求体不参与签名，需要的`Content-MD5`、`Content-Type`等header请在签名前设置好
+    pub fn sign_request(
+        &self,
+        creds: &Credentials,
+        request: &mut reqwest::Request,
+        bucket: Option<&str>,
+    ) -> Result<(), Error> {
+        let method = request.method().clone();
+        let url = request.url().clone();
+        self.sign_parts(creds, &method, &url, request.headers_mut(), bucket)
+    }
+
+    /// 对`http::Request`签名，`uri`必须是包含scheme和host的绝对地址
+    pub fn sign_http_request<B>(
+        &self,
+        creds: &Credentials,
+        request: &mut http::Request<B>,
+        bucket: Option<&str>,
+    ) -> Result<(), Error> {
+        let method = request.method().clone();
+        let uri = request.uri().to_string();
+        let url = Url::parse(&uri)
+            .map_err(|e| Error::Common(format!("invalid request uri `{}`: {}", uri, e)))?;
+        self.sign_parts(creds, &method, &url, request.headers_mut(), bucket)
+    }
+
+    fn sign_parts(
+        &self,
+        creds: &Credentials,
+        method: &Method,
+        url: &Url,
+        headers: &mut HeaderMap,
+        bucket: Option<&str>,
+    ) -> Result<(), Error> {
+        let method = HTTPVerb::try_from(method)?;
+        let mut header_map = HashMap::with_capacity(headers.len());
+        for (k, v) in headers.iter() {
+            let v = v
+                .to_str()
+                .map_err(|e| Error::Common(format!("invalid header `{}`: {}", k, e)))?;
+            header_map.insert(k.as_str().to_owned(), v.to_owned());
+        }
+        let signed = self.sign_headers(creds, method, url, header_map, bucket)?;
+        headers.extend(signed);
+        Ok(())
+    }
+}
+
+impl Client {
+    /// 返回和当前client使用相同region、签名版本和时钟偏移的[`Signer`]
+    pub fn signer(&self) -> Signer {
+        Signer {
+            region: self.region.clone(),
+            signature_version: self.signature_version,
+            clock_offset: self.clock_offset.clone(),
+        }
+    }
+}
+
+#[test]
+fn signer_test() {
+    let creds = Credentials::new("id", "secret", Some("token".to_owned()), None);
+    let signer = Signer::builder().region("cn-hangzhou").build();
+
+    let client = reqwest::Client::new();
+    let mut req = client
+        .get("https://example.oss-cn-hangzhou.aliyuncs.com/a.txt?tagging")
+        .header("x-oss-meta-a", "b")
+        .build()
+        .unwrap();
+    signer
+        .sign_request(&creds, &mut req, Some("example"))
+        .unwrap();
+    let headers = req.headers();
+    assert!(
+        headers["authorization"]
+            .to_str()
+            .unwrap()
+            .starts_with("OSS4-HMAC-SHA256 Credential=id/")
+    );
+    assert_eq!(headers["x-oss-security-token"], "token");
+    assert!(headers["x-oss-security-token"].is_sensitive());
+    assert_eq!(headers["x-oss-content-sha256"], "UNSIGNED-PAYLOAD");
+    assert_eq!(headers["x-oss-meta-a"], "b");
+    assert!(headers.contains_key("date"));
+
+    let signer = Signer::builder()
+        .region("cn-hangzhou")
+        .signature_version(SignatureVersion::V1)
+        .build();
+    let mut req = http::Request::builder()
+        .method("DELETE")
+        .uri("https://oss-cn-hangzhou.aliyuncs.com/example/a.txt")
+        .body(())
+        .unwrap();
+    signer.sign_http_request(&creds, &mut req, None).unwrap();
+    assert!(
+        req.headers()["authorization"]
+            .to_str()
+            .unwrap()
+            .starts_with("OSS id:")
+    );
+    assert_eq!(req.headers()["x-oss-security-token"], "token");
+}
@@ -1,15 +1,32 @@
-use crate::oss::Error;
-use crate::oss::sign_v4::{HTTPVerb, SignV4Param, generate_v4_signature, sign_v4};
+use crate::credentials::Credentials;
+use crate::oss::endpoint::host_header;
+use crate::oss::sign_v1::{SignV1Param, generate_v1_signature};
+use crate::oss::sign_v4::{
+    HTTPVerb, SignV4Param, UNSIGNED_PAYLOAD, generate_v4_signature, sign_v4,
+};
+use crate::oss::{Client, Error};
 use base64::{Engine, engine::general_purpose};
+use bytes::Bytes;
 use md5::{Digest, Md5};
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Body, StatusCode};
 use serde::de::DeserializeOwned;
-use serde_json::{Map, Value};
+use sha2::Sha256;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Display;
 use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc2822;
 use time::macros::format_description;
 use tokio::io::AsyncReadExt;
+use tokio_util::io::ReaderStream;
+use u_sdk_common::credentials::load_credentials;
 use u_sdk_common::helper::gmt_format;
+pub(crate) use u_sdk_common::helper::into_request_failed_error;
+use u_sdk_common::retry::{is_idempotent_method, send_with_retry};
+use u_sdk_common::transport::new_request;
 use url::Url;
 
 pub fn utc_date_str(date_time: &time::OffsetDateTime) -> String {
@@ -64,16 +81,15 @@ pub(crate) fn into_request_header(map: HashMap<&str, &str>) -> HeaderMap {
         .collect()
 }
 
-pub(crate) async fn into_request_failed_error(resp: reqwest::Response) -> Error {
-    let status = resp.status();
-    let body = resp.text().await;
-    match body {
-        Ok(text) => Error::RequestAPIFailed {
-            status: status.to_string(),
-            text,
-        },
-        Err(e) => Error::Reqwest(e),
+const SECURITY_TOKEN: &str = "x-oss-security-token";
+
+// 签名后的请求头，token标记为sensitive，不会出现在Debug输出中
+fn into_signed_header(map: HashMap<&str, &str>) -> HeaderMap {
+    let mut header = into_request_header(map);
+    if let Some(token) = header.get_mut(SECURITY_TOKEN) {
+        token.set_sensitive(true);
     }
+    header
 }
 
 // TODO 放到common-lib中供全局使用
@@ -92,10 +108,19 @@ pub(crate) async fn parse_xml_response<T: DeserializeOwned>(
     Ok(data)
 }
 
-// 用 buffer 读文件并计算MD5
-pub(crate) async fn compute_md5_from_file(path: &Path) -> Result<String, Error> {
+/// 计算payload的SHA-256，结果为小写的十六进制字符串，用于`x-oss-content-sha256`
+pub(crate) fn get_content_sha256(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+// 用 buffer 读文件并计算MD5，`with_sha256`为`true`时在同一次读取中计算SHA-256
+pub(crate) async fn compute_digest_from_file(
+    path: &Path,
+    with_sha256: bool,
+) -> Result<(String, Option<String>), Error> {
     let mut file = tokio::fs::File::open(path).await?;
     let mut hasher = Md5::new();
+    let mut sha256_hasher = with_sha256.then(Sha256::new);
     // 放到堆上并初始化
     let mut buf = vec![0u8; 64 * 1024]; // 64KB buffer
 
@@ -105,10 +130,14 @@ pub(crate) async fn compute_md5_from_file(path: &Path) -> Result<String, Error>
             break;
         }
         hasher.update(&buf[..n]);
+        if let Some(sha256_hasher) = &mut sha256_hasher {
+            sha256_hasher.update(&buf[..n]);
+        }
     }
 
-    let result = hasher.finalize();
-    Ok(general_purpose::STANDARD.encode(result))
+    let md5 = general_purpose::STANDARD.encode(hasher.finalize());
+    let sha256 = sha256_hasher.map(|h| hex::encode(h.finalize()));
+    Ok((md5, sha256))
 }
 
 pub(crate) fn validate_object_name(name: &str) -> Result<(), Error> {
@@ -194,15 +223,111 @@ fn validate_object_name_test() {
     assert!(validate_object_name("abc/../def").is_err());
 }
 
+/// 签名后发送的请求体，时钟偏移重试时需要重新构建
+pub(crate) enum SignedBody<'a> {
+    Empty,
+    Bytes(Bytes),
+    // 每次发送时重新打开文件
+    File(&'a Path),
+}
+
+// OSS允许的签名时间和服务端时间的最大偏差
+const MAX_CLOCK_SKEW: time::Duration = time::Duration::minutes(15);
+
+impl Client {
+    /// 加载凭证，失败时返回[`Error::Credentials`]
+    pub(crate) async fn load_credentials(&self) -> Result<Arc<Credentials>, Error> {
+        load_credentials(self.credentials_provider.as_ref()).await
+    }
+
+    /// 按照client的签名版本计算签名，返回最终的请求头
+    ///
+    /// - `bucket`：使用[`Client::signing_bucket`]获取
+    pub(crate) fn request_header(
+        &self,
+        creds: &Credentials,
+        req_header_map: HashMap<String, String>,
+        request_url: &Url,
+        http_verb: HTTPVerb,
+        bucket: Option<&str>,
+    ) -> Result<HeaderMap, Error> {
+        self.signer()
+            .sign_headers(creds, http_verb, request_url, req_header_map, bucket)
+    }
+
+    /// 签名并发送请求，失败时按照client的重试策略重试，每次重试都会重新签名并重新构建请求体
+    ///
+    /// 服务端返回403且响应的`Date`和签名时间相差超过15分钟时（`RequestTimeTooSkewed`），
+    /// 根据`Date`校正client的时钟偏移后重新签名，并自动重试一次
+    pub(crate) async fn send_signed(
+        &self,
+        creds: &Credentials,
+        http_verb: HTTPVerb,
+        request_url: Url,
+        req_header_map: HashMap<String, String>,
+        bucket: Option<&str>,
+        body: SignedBody<'_>,
+    ) -> Result<reqwest::Response, Error> {
+        let method = reqwest::Method::from(http_verb);
+        let idempotent = is_idempotent_method(&method);
+        let (method, req_header_map, request_url, body) =
+            (&method, &req_header_map, &request_url, &body);
+        let make_request = move || async move {
+            let header_map = self.request_header(
+                creds,
+                req_header_map.clone(),
+                request_url,
+                http_verb,
+                bucket,
+            )?;
+            let body = match body {
+                SignedBody::Empty => None,
+                SignedBody::Bytes(bytes) => Some(Body::from(bytes.clone())),
+                SignedBody::File(path) => {
+                    let file = tokio::fs::File::open(path).await?;
+                    Some(Body::wrap_stream(ReaderStream::new(file)))
+                }
+            };
+            Ok::<_, Error>(new_request(
+                method.clone(),
+                request_url.clone(),
+                header_map,
+                body,
+            ))
+        };
+
+        let mut skew_retried = false;
+        loop {
+            let resp = send_with_retry(
+                &self.retry_policy,
+                self.http_client.as_ref(),
+                idempotent,
+                make_request,
+            )
+            .await?;
+
+            if !skew_retried
+                && resp.status() == StatusCode::FORBIDDEN
+                && self.clock_offset.is_skewed(resp.headers(), MAX_CLOCK_SKEW)
+            {
+                self.clock_offset.sync_from_headers(resp.headers());
+                skew_retried = true;
+                continue;
+            }
+            return Ok(resp);
+        }
+    }
+}
+
 pub(crate) fn get_request_header(
-    access_key_id: &str,
-    access_key_secret: &str,
+    creds: &Credentials,
     req_header_map: HashMap<String, String>,
     request_url: &Url,
     http_verb: HTTPVerb,
     signing_region: &str,
     bucket: Option<&str>,
-) -> HeaderMap {
+    now: &OffsetDateTime,
+) -> Result<HeaderMap, Error> {
     // 把需要签名的header和不需要签名的header分开
     let (sign_map, remaining_map) = partition_header(req_header_map);
 
@@ -210,15 +335,21 @@ pub(crate) fn get_request_header(
     let mut canonical_header = BTreeMap::new();
     canonical_header.extend(sign_map.iter().map(|(k, v)| (k.as_str(), v.as_str())));
 
-    // x-oss-content-sha256是必须存在且值固定
-    canonical_header.insert("x-oss-content-sha256", "UNSIGNED-PAYLOAD");
+    // x-oss-content-sha256必须存在，调用者没有计算payload的SHA-256时使用UNSIGNED-PAYLOAD
+    canonical_header
+        .entry("x-oss-content-sha256")
+        .or_insert(UNSIGNED_PAYLOAD);
     // host为addition_header中指定的需要额外添加到签名计算中的参数
-    canonical_header.insert("host", request_url.host_str().unwrap());
+    let host = host_header(request_url)?;
+    canonical_header.insert("host", &host);
+    // STS的token需要参与签名，直接借用`Secret`中的值
+    if let Some(token) = &creds.sts_security_token {
+        canonical_header.insert(SECURITY_TOKEN, token.expose_secret());
+    }
 
     // 添加host到additional_header，因为canonical_header中把host也参与签名计算了
     let mut additional_header = BTreeSet::new();
     additional_header.insert("host");
-    let now = time::OffsetDateTime::now_utc();
     let sign_v4_param = SignV4Param {
         signing_region,
         http_verb,
@@ -226,18 +357,22 @@ pub(crate) fn get_request_header(
         bucket,
         header_map: &canonical_header,
         additional_header: Some(&additional_header),
-        date_time: &now,
+        date_time: now,
     };
-    let authorization = sign_v4(access_key_id, access_key_secret, sign_v4_param);
+    let authorization = sign_v4(
+        &creds.access_key_id,
+        creds.access_key_secret.expose_secret(),
+        sign_v4_param,
+    );
 
     // 把canonical_header转化为最终的header，补齐剩下的未参与签名计算的header
     // 包括：剩下必要的公共请求头，api header中的非签名字段
     let mut header = canonical_header.into_iter().collect::<HashMap<_, _>>();
     header.insert("Authorization", &authorization);
-    let gmt = gmt_format(&now);
+    let gmt = gmt_format(now);
     header.insert("Date", &gmt);
     header.extend(remaining_map.iter().map(|(k, v)| (k.as_str(), v.as_str())));
-    into_request_header(header)
+    Ok(into_signed_header(header))
 }
 
 pub(crate) fn get_date_str(data: &time::OffsetDateTime) -> String {
@@ -257,15 +392,17 @@ pub(crate) struct PresignParams<'a> {
     pub presigned_url: Url,
     pub http_verb: HTTPVerb,
     pub url_expires: i32,
-    pub bucket: &'a str,
+    pub bucket: Option<&'a str>,
     pub signing_region: &'a str,
+    // 签名使用的时间
+    pub now: OffsetDateTime,
 }
 
-pub(crate) fn generate_presigned_url(mut params: PresignParams<'_>) -> String {
+pub(crate) fn generate_presigned_url(mut params: PresignParams<'_>) -> Result<String, Error> {
     let (header_map, remaining_map) = partition_header(params.header_map);
     let mut canonical_header = BTreeMap::new();
     canonical_header.extend(header_map.iter().map(|(k, v)| (k.as_str(), v.as_str())));
-    let host = params.presigned_url.host_str().unwrap().to_owned();
+    let host = host_header(&params.presigned_url)?;
     canonical_header.insert("host", host.as_str());
 
     let mut additional_header = BTreeSet::new();
@@ -276,7 +413,7 @@ pub(crate) fn generate_presigned_url(mut params: PresignParams<'_>) -> String {
         additional_header.insert(k.as_str());
     }
 
-    let now = time::OffsetDateTime::now_utc();
+    let now = params.now;
     // 添加参与签名计算的query
     params
         .presigned_url
@@ -308,7 +445,7 @@ pub(crate) fn generate_presigned_url(mut params: PresignParams<'_>) -> String {
         signing_region: params.signing_region,
         http_verb: params.http_verb,
         uri: &params.presigned_url,
-        bucket: Some(params.bucket),
+        bucket: params.bucket,
         header_map: &canonical_header,
         additional_header: Some(&additional_header),
         date_time: &now,
@@ -321,7 +458,78 @@ pub(crate) fn generate_presigned_url(mut params: PresignParams<'_>) -> String {
         .append_pair("x-oss-signature", &signature)
         .finish();
 
-    params.presigned_url.to_string()
+    Ok(params.presigned_url.to_string())
+}
+
+pub(crate) fn get_v1_request_header(
+    creds: &Credentials,
+    req_header_map: HashMap<String, String>,
+    request_url: &Url,
+    http_verb: HTTPVerb,
+    bucket: Option<&str>,
+    now: &OffsetDateTime,
+) -> Result<HeaderMap, Error> {
+    let mut header_map = req_header_map
+        .into_iter()
+        .map(|(k, v)| (k.to_lowercase(), v))
+        .collect::<BTreeMap<_, _>>();
+    // V1签名不校验payload，不需要x-oss-content-sha256
+    header_map.remove("x-oss-content-sha256");
+    let mut header_map = header_map
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect::<BTreeMap<_, _>>();
+    if let Some(token) = &creds.sts_security_token {
+        header_map.insert(SECURITY_TOKEN, token.expose_secret());
+    }
+
+    let gmt = gmt_format(now);
+    let sign_v1_param = SignV1Param {
+        http_verb,
+        uri: request_url,
+        bucket,
+        header_map: &header_map,
+        date_or_expires: &gmt,
+    };
+    let signature = generate_v1_signature(creds.access_key_secret.expose_secret(), sign_v1_param);
+    let authorization = format!("OSS {}:{}", creds.access_key_id, signature);
+
+    let mut header = header_map.into_iter().collect::<HashMap<_, _>>();
+    header.insert("Authorization", &authorization);
+    header.insert("Date", &gmt);
+    Ok(into_signed_header(header))
+}
+
+pub(crate) fn generate_v1_presigned_url(mut params: PresignParams<'_>) -> Result<String, Error> {
+    let header_map = params
+        .header_map
+        .into_iter()
+        .map(|(k, v)| (k.to_lowercase(), v))
+        .collect::<BTreeMap<_, _>>();
+    let header_map = header_map
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect::<BTreeMap<_, _>>();
+
+    // V1的Expires为URL过期时的unix时间戳
+    let expires = (params.now.unix_timestamp() + params.url_expires as i64).to_string();
+    let sign_v1_param = SignV1Param {
+        http_verb: params.http_verb,
+        uri: &params.presigned_url,
+        bucket: params.bucket,
+        header_map: &header_map,
+        date_or_expires: &expires,
+    };
+    let signature = generate_v1_signature(params.access_key_secret, sign_v1_param);
+    params
+        .presigned_url
+        .query_pairs_mut()
+        .append_pair("OSSAccessKeyId", params.access_key_id)
+        .append_pair("Expires", &expires)
+        .append_pair("Signature", &signature)
+        .finish();
+
+    Ok(params.presigned_url.to_string())
 }
 
 // 将Header分为需要参与签名的Header和剩余Header
@@ -341,25 +549,109 @@ fn partition_header(
     (sign_map, remaining_map)
 }
 
-pub(crate) fn parse_get_object_response_header<T: DeserializeOwned>(
+/// 获取响应头并解析为指定类型，header不存在时返回`None`，存在但格式错误时返回错误
+pub(crate) fn parse_header<T>(header: &HeaderMap, name: &str) -> Result<Option<T>, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let Some(val) = header.get(name) else {
+        return Ok(None);
+    };
+    let s = val
+        .to_str()
+        .map_err(|_| Error::Common(format!("invalid header value: {}", name)))?;
+    s.parse::<T>()
+        .map(Some)
+        .map_err(|e| Error::Common(format!("failed to parse header `{}: {}`: {}", name, s, e)))
+}
+
+/// 和[parse_header]一样，但是header不存在时返回错误
+pub(crate) fn required_header<T>(header: &HeaderMap, name: &str) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    parse_header(header, name)?.ok_or_else(|| Error::Common(format!("missing header: {}", name)))
+}
+
+/// 解析GMT格式的时间，如：`Fri, 24 Feb 2012 06:07:48 GMT`
+pub(crate) fn parse_gmt(s: &str) -> Result<OffsetDateTime, time::error::Parse> {
+    OffsetDateTime::parse(s, &Rfc2822)
+}
+
+pub(crate) fn parse_gmt_header(
     header: &HeaderMap,
-) -> (T, HashMap<String, String>) {
-    let mut map = Map::new();
-    let mut custom_meta_map = HashMap::new();
-    for (name, val) in header {
-        let name_s = name.as_str();
-        if let Ok(s) = val.to_str() {
-            if name_s.starts_with("x-oss-meta-") {
-                custom_meta_map.insert(
-                    name_s.trim_start_matches("x-oss-meta-").to_string(),
-                    s.to_string(),
-                );
-            } else {
-                map.insert(name_s.to_string(), Value::String(s.to_string()));
-            }
-        }
-    }
+    name: &str,
+) -> Result<Option<OffsetDateTime>, Error> {
+    let Some(s) = parse_header::<String>(header, name)? else {
+        return Ok(None);
+    };
+    parse_gmt(&s)
+        .map(Some)
+        .map_err(|e| Error::Common(format!("failed to parse header `{}: {}`: {}", name, s, e)))
+}
 
-    let response_header = serde_json::from_value::<T>(Value::Object(map)).unwrap();
-    (response_header, custom_meta_map)
+#[cfg(test)]
+#[tokio::test]
+async fn send_signed_clock_skew_test() {
+    use crate::credentials::StaticCredentialsProvider;
+    use tokio::io::AsyncWriteExt;
+
+    // 服务端时间比本地快1小时，第一次请求返回403，第二次返回200，记录每次请求的Date
+    let server_time = OffsetDateTime::now_utc() + time::Duration::hours(1);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        let mut request_dates = vec![];
+        for status in ["403 Forbidden", "200 OK"] {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 4096];
+            let n = stream.read(&mut buf).await.unwrap();
+            let req = String::from_utf8_lossy(&buf[..n]).into_owned();
+            let date = req
+                .lines()
+                .find_map(|l| l.strip_prefix("date: "))
+                .unwrap()
+                .to_owned();
+            request_dates.push(date);
+            let resp = format!(
+                "HTTP/1.1 {}\r\nDate: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status,
+                gmt_format(&server_time)
+            );
+            stream.write_all(resp.as_bytes()).await.unwrap();
+        }
+        request_dates
+    });
+
+    let client = Client::builder()
+        .credentials_provider(Arc::new(StaticCredentialsProvider::new(Credentials::new(
+            "id", "secret", None, None,
+        ))))
+        .endpoint(addr.to_string())
+        .region("cn-hangzhou")
+        .bucket("example")
+        .build();
+    let creds = Credentials::new("id", "secret", None, None);
+    let url = Url::parse(&format!("http://{}/example/", addr)).unwrap();
+    let resp = client
+        .send_signed(
+            &creds,
+            HTTPVerb::Get,
+            url,
+            HashMap::new(),
+            None,
+            SignedBody::Empty,
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let request_dates = server.await.unwrap();
+    let first = parse_gmt(&request_dates[0]).unwrap();
+    let second = parse_gmt(&request_dates[1]).unwrap();
+    assert!((first - server_time).abs() > MAX_CLOCK_SKEW);
+    assert!((second - server_time).abs() < time::Duration::seconds(5));
+    assert!(client.clock_offset().offset() > time::Duration::minutes(59));
 }
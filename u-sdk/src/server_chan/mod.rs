@@ -1,16 +1,15 @@
 //! Server chan 3 sdk
 
 use bon::{Builder, bon};
-use reqwest::StatusCode;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, Url};
 use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use u_sdk_common::helper::into_request_failed_error;
+use u_sdk_common::transport::{HttpTransport, new_json_request, transport_with_timeouts};
 
-#[derive(thiserror::Error, Debug)]
-pub enum Error {
-    #[error("request failed: code: {code}\nbody: {body}")]
-    API { code: StatusCode, body: String },
-    #[error("use reqwest error:\n {0}")]
-    Reqwest(#[from] reqwest::Error),
-}
+pub use u_sdk_common::Error;
 
 #[derive(Builder, Serialize)]
 pub struct SendMsg<'a> {
@@ -58,16 +57,29 @@ impl<'a, S: send_msg_builder::State> SendMsgBuilder<'a, S> {
 /// 使用Server酱3
 pub struct Client {
     url: String,
-    http_client: reqwest::Client,
+    http_client: Arc<dyn HttpTransport>,
 }
 
 #[bon]
 impl Client {
+    /// - `http_client`：`reqwest::Client`或其它[`HttpTransport`]，默认为`reqwest::Client::new()`
+    /// - `connect_timeout`、`read_timeout`、`timeout`：连接、读取和单次请求的总超时，
+    ///   只在没有指定`http_client`时生效，默认不限制
     #[builder]
-    pub fn new(uid: i32, key: &str) -> Self {
+    pub fn new(
+        uid: i32,
+        key: &str,
+        #[builder(with = |http_client: impl HttpTransport + 'static| Arc::new(http_client))]
+        http_client: Option<Arc<dyn HttpTransport>>,
+        connect_timeout: Option<Duration>,
+        read_timeout: Option<Duration>,
+        timeout: Option<Duration>,
+    ) -> Self {
+        let http_client = http_client
+            .unwrap_or_else(|| transport_with_timeouts(connect_timeout, read_timeout, timeout));
         Self {
             url: format!("https://{}.push.ft07.com/send/{}.send", uid, key),
-            http_client: reqwest::Client::new(),
+            http_client,
         }
     }
 
@@ -79,17 +91,12 @@ impl Client {
 impl SendMsg<'_> {
     pub async fn send(&self) -> Result<(), Error> {
         let client = self.client;
-        let resp = client
-            .http_client
-            .post(&client.url)
-            .json(self)
-            .send()
-            .await?;
+        let url = Url::parse(&client.url)
+            .map_err(|e| Error::Common(format!("invalid url `{}`: {}", client.url, e)))?;
+        let req = new_json_request(Method::POST, url, HeaderMap::new(), self)?;
+        let resp = client.http_client.execute(req).await?;
         if !resp.status().is_success() {
-            return Err(Error::API {
-                code: resp.status(),
-                body: resp.text().await.unwrap_or_default(),
-            });
+            return Err(into_request_failed_error(resp).await);
         }
 
         Ok(())
@@ -1,101 +1,346 @@
 //! STS (Security Token Service) client for assuming roles and obtaining temporary security credentials.
 //!
+//! 支持`AssumeRole`、`AssumeRoleWithSAML`、`AssumeRoleWithOIDC`和`GetCallerIdentity`，
+//! 其中`AssumeRoleWithSAML`和`AssumeRoleWithOIDC`为匿名调用，不需要AccessKey
+//!
 //! [官方文档](https://help.aliyun.com/zh/ram/developer-reference/api-sts-2015-04-01-assumerole)
-mod error;
+mod provider;
 pub mod ram_policy;
 mod types;
 
-pub use error::Error;
+pub use provider::AssumeRoleCredentialsProvider;
 pub use types::*;
+pub use u_sdk_common::Error;
 
-use crate::credentials::CredentialsProvider;
+use crate::credentials::{CredentialsProvider, DefaultCredentialsChain};
 use bon::bon;
+use reqwest::Method;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use std::sync::Arc;
-use u_sdk_common::helper::into_header_map;
-use u_sdk_common::open_api_sign::{OpenApiStyle, SignParams, get_openapi_request_header};
+use std::time::Duration;
+use u_sdk_common::open_api::{OpenApiClient, RequestBody};
+use u_sdk_common::open_api_sign::to_query_map;
+use u_sdk_common::retry::RetryPolicy;
+use u_sdk_common::transport::HttpTransport;
 
 //region client
+/// `Client`的clone开销很小，clone后共享同一个`CredentialsProvider`和http连接池
+#[derive(Clone)]
 pub struct Client {
-    credentials_provider: Arc<dyn CredentialsProvider>,
-    http_client: reqwest::Client,
-    host: String,
-    style: OpenApiStyle,
+    api: OpenApiClient,
 }
 
 #[bon]
 impl Client {
     #[builder(on(String, into))]
     pub fn new(
+        /// 默认为[`DefaultCredentialsChain`]
+        #[builder(default = Arc::new(DefaultCredentialsChain::new()))]
         credentials_provider: Arc<dyn CredentialsProvider>,
         /// 参数host: [host地址](https://help.aliyun.com/zh/ram/developer-reference/api-sts-2015-04-01-endpoint)
         host: String,
+        /// `reqwest::Client`或其它[`HttpTransport`]，默认为`reqwest::Client::new()`
+        #[builder(with = |http_client: impl HttpTransport + 'static| Arc::new(http_client))]
+        http_client: Option<Arc<dyn HttpTransport>>,
+        /// 重试策略，默认为[`RetryPolicy::default`]
+        #[builder(default)]
+        retry_policy: RetryPolicy,
+        /// 建立连接的超时，只在没有指定`http_client`时生效，默认不限制
+        connect_timeout: Option<Duration>,
+        /// 每次读取响应数据的超时，只在没有指定`http_client`时生效，默认不限制
+        read_timeout: Option<Duration>,
+        /// 单次请求的总超时，只在没有指定`http_client`时生效，默认不限制
+        timeout: Option<Duration>,
     ) -> Self {
         Self {
-            credentials_provider,
-            http_client: reqwest::Client::new(),
-            host,
-            style: OpenApiStyle::RPC,
+            api: OpenApiClient::builder()
+                .credentials_provider(credentials_provider)
+                .host(host)
+                .maybe_http_client(http_client)
+                .retry_policy(retry_policy)
+                .maybe_connect_timeout(connect_timeout)
+                .maybe_read_timeout(read_timeout)
+                .maybe_timeout(timeout)
+                .build(),
+        }
+    }
+
+    /// 返回使用`credentials_provider`的client，共享http连接池和其它配置
+    ///
+    /// 可以传入[`AssumeRoleCredentialsProvider`]，使用扮演角色得到的临时凭证再扮演其它角色（角色链），
+    /// 如跨账号扮演角色
+    pub fn with_credentials_provider(
+        &self,
+        credentials_provider: Arc<dyn CredentialsProvider>,
+    ) -> Self {
+        Self {
+            api: self.api.with_credentials_provider(credentials_provider),
         }
     }
 
     pub fn assume_role(&self) -> AssumeRoleBuilder<'_> {
         AssumeRole::builder(self)
     }
-}
-//endregion
 
-async fn parse_json_response<T: serde::de::DeserializeOwned>(
-    resp: reqwest::Response,
-) -> Result<T, Error> {
-    let status = resp.status();
-    if !status.is_success() {
-        return Err(Error::RequestAPIFailed {
-            status: status.to_string(),
-            text: resp.text().await?,
-        });
+    pub fn assume_role_with_saml(&self) -> AssumeRoleWithSAMLBuilder<'_> {
+        AssumeRoleWithSAML::builder(self)
+    }
+
+    pub fn assume_role_with_oidc(&self) -> AssumeRoleWithOIDCBuilder<'_> {
+        AssumeRoleWithOIDC::builder(self)
     }
 
-    let bytes = resp.bytes().await?;
-    let data: T = serde_json::from_slice(&bytes).map_err(|e| {
-        Error::Common(format!(
-            "parse response json error: {}, response text: {}",
-            e,
-            String::from_utf8_lossy(&bytes)
-        ))
-    })?;
+    pub fn get_caller_identity(&self) -> GetCallerIdentityBuilder<'_> {
+        GetCallerIdentity::builder(self)
+    }
+
+    // 参数放在query中，使用client的凭证签名
+    //
+    // `idempotent`由调用处按照API设置；STS的API只签发临时凭证或只读，都可以安全地重试
+    async fn call<T: DeserializeOwned>(
+        &self,
+        action: &str,
+        method: Method,
+        query: impl Serialize,
+        idempotent: bool,
+    ) -> Result<T, Error> {
+        let query = serde_json::to_value(query)
+            .map_err(|e| Error::Common(format!("serialize query failed: {}", e)))?;
+        self.api
+            .request(action, "2015-04-01")
+            .method(method)
+            .query(query)
+            .idempotent(idempotent)
+            .build()
+            .send()
+            .await
+    }
 
-    Ok(data)
+    // 匿名调用，不加载凭证，也不添加Authorization；
+    // 参数作为`application/x-www-form-urlencoded`的body发送，SAML断言和OIDC token不会出现在url中
+    async fn call_anonymous<T: DeserializeOwned>(
+        &self,
+        action: &str,
+        form: impl Serialize,
+        idempotent: bool,
+    ) -> Result<T, Error> {
+        let form = to_query_map(form, None)?;
+        let form: Vec<_> = form.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        self.api
+            .request(action, "2015-04-01")
+            .method(Method::POST)
+            .body(RequestBody::FormData(&form))
+            .idempotent(idempotent)
+            .anonymous(true)
+            .build()
+            .send()
+            .await
+    }
 }
+//endregion
 
 impl AssumeRole<'_> {
     pub async fn send(&self) -> Result<AssumeRoleResponse, Error> {
-        let client = self.client;
-        let creds = client.credentials_provider.load().await?;
-
-        let sign_params = SignParams {
-            req_method: "GET",
-            host: &client.host,
-            query_map: self,
-            x_acs_action: "AssumeRole",
-            x_acs_version: "2015-04-01",
-            x_acs_security_token: creds.sts_security_token.as_deref(),
-            request_body: None,
-            style: &client.style,
-        };
-
-        let (common_headers, url_) =
-            get_openapi_request_header(&creds.access_key_secret, &creds.access_key_id, sign_params)
-                .map_err(|e| Error::Common(format!("get_openapi_request_header error: {}", e)))?;
-
-        let header_map = into_header_map(common_headers);
-        let resp = client
-            .http_client
-            .get(url_)
-            .headers(header_map)
-            .send()
-            .await?;
+        self.client
+            .call("AssumeRole", Method::GET, self, true)
+            .await
+    }
+}
 
-        let res = parse_json_response(resp).await?;
-        Ok(res)
+impl AssumeRoleWithSAML<'_> {
+    pub async fn send(&self) -> Result<AssumeRoleWithSAMLResponse, Error> {
+        self.client
+            .call_anonymous("AssumeRoleWithSAML", self, true)
+            .await
     }
 }
+
+impl AssumeRoleWithOIDC<'_> {
+    pub async fn send(&self) -> Result<AssumeRoleWithOIDCResponse, Error> {
+        self.client
+            .call_anonymous("AssumeRoleWithOIDC", self, true)
+            .await
+    }
+}
+
+impl GetCallerIdentity<'_> {
+    pub async fn send(&self) -> Result<GetCallerIdentityResponse, Error> {
+        // 没有请求参数
+        let query = serde_json::Map::new();
+        self.client
+            .call("GetCallerIdentity", Method::POST, query, true)
+            .await
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn mock_transport_test() {
+    use crate::credentials::{Credentials, StaticCredentialsProvider};
+    use u_sdk_common::transport::HttpTransport;
+
+    // 检查请求并返回固定的响应，不发送网络请求
+    struct MockTransport;
+    #[async_trait::async_trait]
+    impl HttpTransport for MockTransport {
+        async fn execute(
+            &self,
+            request: reqwest::Request,
+        ) -> Result<reqwest::Response, u_sdk_common::Error> {
+            assert_eq!(
+                request.url().host_str(),
+                Some("sts.cn-hangzhou.aliyuncs.com")
+            );
+            let query = request.url().query().unwrap();
+            assert!(query.contains("RoleArn=acs%3Aram%3A%3A123%3Arole%2Ftest"));
+            assert_eq!(request.headers()["x-acs-action"], "AssumeRole");
+            assert!(
+                request.headers()["authorization"]
+                    .to_str()
+                    .unwrap()
+                    .starts_with("ACS3-HMAC-SHA256 Credential=id,")
+            );
+
+            let body = r#"{
+                "RequestId": "req-1",
+                "AssumedRoleUser": {"AssumedRoleId": "role-id:s", "Arn": "acs:ram::123:role/test/s"},
+                "Credentials": {
+                    "SecurityToken": "token",
+                    "Expiration": "2025-12-01T00:00:00Z",
+                    "AccessKeyId": "STS.id",
+                    "AccessKeySecret": "sts-secret"
+                }
+            }"#;
+            Ok(http::Response::builder()
+                .status(200)
+                .body(body)
+                .unwrap()
+                .into())
+        }
+    }
+
+    let client = Client::builder()
+        .credentials_provider(Arc::new(StaticCredentialsProvider::new(Credentials::new(
+            "id", "secret", None, None,
+        ))))
+        .host("sts.cn-hangzhou.aliyuncs.com")
+        .http_client(MockTransport)
+        .build();
+    let res = client
+        .assume_role()
+        .role_arn("acs:ram::123:role/test")
+        .role_session_name("s")
+        .build()
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.request_id, "req-1");
+    assert_eq!(res.credentials.access_key_id, "STS.id");
+
+    let creds: Credentials = res.try_into().unwrap();
+    assert_eq!(
+        creds.sts_security_token.as_ref().map(|t| t.expose_secret()),
+        Some("token")
+    );
+    assert_eq!(
+        creds.expires_at,
+        Some(time::macros::datetime!(2025-12-01 00:00 UTC))
+    );
+    assert!(creds.is_expired());
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn anonymous_call_test() {
+    use crate::credentials::{Credentials, StaticCredentialsProvider};
+    use u_sdk_common::transport::HttpTransport;
+
+    struct MockTransport;
+    #[async_trait::async_trait]
+    impl HttpTransport for MockTransport {
+        async fn execute(
+            &self,
+            request: reqwest::Request,
+        ) -> Result<reqwest::Response, u_sdk_common::Error> {
+            let action = request.headers()["x-acs-action"].to_str().unwrap();
+            let query = request.url().query().unwrap_or_default();
+            let body = match action {
+                "AssumeRoleWithOIDC" => {
+                    assert!(!request.headers().contains_key("authorization"));
+                    assert_eq!(
+                        request.headers()["content-type"],
+                        "application/x-www-form-urlencoded"
+                    );
+                    // token只在body中，不出现在url中
+                    assert!(!query.contains("OIDCToken"));
+                    let form =
+                        std::str::from_utf8(request.body().unwrap().as_bytes().unwrap()).unwrap();
+                    assert!(
+                        form.contains("OIDCProviderArn=acs%3Aram%3A%3A123%3Aoidc-provider%2Fack")
+                    );
+                    assert!(form.contains("OIDCToken=token"));
+                    r#"{
+                        "RequestId": "req-1",
+                        "AssumedRoleUser": {"AssumedRoleId": "role-id:s", "Arn": "acs:ram::123:role/test/s"},
+                        "Credentials": {
+                            "SecurityToken": "token",
+                            "Expiration": "2025-12-01T00:00:00Z",
+                            "AccessKeyId": "STS.id",
+                            "AccessKeySecret": "sts-secret"
+                        },
+                        "OIDCTokenInfo": {
+                            "Subject": "sub",
+                            "Issuer": "https://oidc.example.com",
+                            "ClientIds": "sts.aliyuncs.com",
+                            "ExpirationTime": "2025-12-01T00:00:00Z",
+                            "IssuanceTime": "2025-11-30T23:00:00Z"
+                        }
+                    }"#
+                }
+                "GetCallerIdentity" => {
+                    assert!(request.headers().contains_key("authorization"));
+                    r#"{
+                        "RequestId": "req-2",
+                        "IdentityType": "RAMUser",
+                        "AccountId": "123",
+                        "PrincipalId": "456",
+                        "UserId": "456",
+                        "Arn": "acs:ram::123:user/test"
+                    }"#
+                }
+                _ => unreachable!(),
+            };
+            Ok(http::Response::builder()
+                .status(200)
+                .body(body)
+                .unwrap()
+                .into())
+        }
+    }
+
+    let client = Client::builder()
+        .credentials_provider(Arc::new(StaticCredentialsProvider::new(Credentials::new(
+            "id", "secret", None, None,
+        ))))
+        .host("sts.cn-hangzhou.aliyuncs.com")
+        .http_client(MockTransport)
+        .build();
+
+    let res = client
+        .assume_role_with_oidc()
+        .oidc_provider_arn("acs:ram::123:oidc-provider/ack")
+        .role_arn("acs:ram::123:role/test")
+        .oidc_token("token")
+        .build()
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.credentials.access_key_id, "STS.id");
+    assert_eq!(res.oidc_token_info.subject, "sub");
+
+    let res = client.get_caller_identity().build().send().await.unwrap();
+    assert_eq!(res.identity_type, "RAMUser");
+    assert_eq!(res.arn, "acs:ram::123:user/test");
+    assert_eq!(res.role_id, None);
+}
@@ -0,0 +1,273 @@
+//! 自动刷新的AssumeRole凭证提供者
+
+use super::Client;
+use super::ram_policy::Policy;
+use crate::credentials::cache::CredentialsCache;
+use crate::credentials::{Credentials, CredentialsProvider};
+use bon::bon;
+use std::sync::Arc;
+use std::time::Duration;
+use u_sdk_common::Error;
+
+/// 使用STS AssumeRole获取临时凭证的[`CredentialsProvider`]
+///
+/// - 缓存获取到的凭证，在过期前`refresh_margin`开始刷新
+/// - 多个请求同时需要刷新时，只会调用一次AssumeRole，其它请求等待并使用刷新后的凭证
+/// - 刷新遇到可重试的错误（如限流、5xx、网络错误）且缓存的凭证还没有过期时，继续使用缓存的凭证
+///
+/// ```ignore
+/// let provider = AssumeRoleCredentialsProvider::builder()
+///     .client(sts_client)
+///     .role_arn("acs:ram::123456789012****:role/adminrole")
+///     .role_session_name("session")
+///     .build();
+/// let oss_client = oss::Client::builder()
+///     .credentials_provider(Arc::new(provider))
+///     ...
+/// ```
+///
+/// 角色链：使用扮演角色得到的临时凭证再扮演其它角色，如先扮演本账号的角色，再扮演其它账号授权给这个角色的角色，
+/// 角色链得到的凭证有效期最长为1小时
+///
+/// ```ignore
+/// let first = AssumeRoleCredentialsProvider::builder()
+///     .client(sts_client.clone())
+///     .role_arn("acs:ram::123456789012****:role/hop")
+///     .role_session_name("session")
+///     .build();
+/// let second = AssumeRoleCredentialsProvider::builder()
+///     .client(sts_client.with_credentials_provider(Arc::new(first)))
+///     .role_arn("acs:ram::987654321098****:role/target")
+///     .role_session_name("session")
+///     .build();
+/// ```
+pub struct AssumeRoleCredentialsProvider {
+    client: Client,
+    role_arn: String,
+    role_session_name: String,
+    duration_seconds: Option<u32>,
+    policy: Option<Policy>,
+    external_id: Option<String>,
+    cache: CredentialsCache,
+}
+
+#[bon]
+impl AssumeRoleCredentialsProvider {
+    /// - `client`：调用AssumeRole使用的STS client
+    /// - `duration_seconds`、`policy`、`external_id`：同[`super::AssumeRole`]
+    /// - `refresh_margin`：在凭证过期前多久开始刷新，默认为5分钟
+    #[builder(on(String, into))]
+    pub fn new(
+        client: Client,
+        role_arn: String,
+        role_session_name: String,
+        duration_seconds: Option<u32>,
+        policy: Option<Policy>,
+        external_id: Option<String>,
+        #[builder(default = Duration::from_secs(300))] refresh_margin: Duration,
+    ) -> Self {
+        Self {
+            client,
+            role_arn,
+            role_session_name,
+            duration_seconds,
+            policy,
+            external_id,
+            cache: CredentialsCache::new(refresh_margin),
+        }
+    }
+
+    async fn refresh(&self) -> Result<Credentials, Error> {
+        let res = self
+            .client
+            .assume_role()
+            .role_arn(&self.role_arn)
+            .role_session_name(&self.role_session_name)
+            .maybe_duration_seconds(self.duration_seconds)
+            .maybe_policy(self.policy.clone())
+            .maybe_external_id(self.external_id.as_deref())
+            .build()
+            .send()
+            .await?;
+        res.try_into()
+    }
+}
+
+#[async_trait::async_trait]
+impl CredentialsProvider for AssumeRoleCredentialsProvider {
+    async fn load(
+        &self,
+    ) -> Result<Arc<Credentials>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        let clock = self.client.api.clock_offset();
+        Ok(self
+            .cache
+            .get_or_refresh(|| clock.now(), self.refresh())
+            .await?)
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn assume_role_provider_test() {
+    use crate::credentials::StaticCredentialsProvider;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use time::OffsetDateTime;
+    use time::format_description::well_known::Rfc3339;
+    use u_sdk_common::retry::RetryPolicy;
+    use u_sdk_common::transport::HttpTransport;
+
+    // 第n次请求返回`STS.n`，凭证在`expires_in`后过期，`fail`时返回503
+    struct MockTransport {
+        calls: Arc<AtomicU32>,
+        expires_in: time::Duration,
+        fail: Arc<std::sync::atomic::AtomicBool>,
+    }
+    #[async_trait::async_trait]
+    impl HttpTransport for MockTransport {
+        async fn execute(&self, _: reqwest::Request) -> Result<reqwest::Response, Error> {
+            tokio::task::yield_now().await;
+            if self.fail.load(Ordering::SeqCst) {
+                return Ok(http::Response::builder()
+                    .status(503)
+                    .body(r#"{"RequestId":"1","Code":"ServiceUnavailable","Message":""}"#)
+                    .unwrap()
+                    .into());
+            }
+            let n = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            let expiration = (OffsetDateTime::now_utc() + self.expires_in)
+                .format(&Rfc3339)
+                .unwrap();
+            let body = format!(
+                r#"{{
+                    "RequestId": "req-{n}",
+                    "AssumedRoleUser": {{"AssumedRoleId": "role-id:s", "Arn": "acs:ram::123:role/test/s"}},
+                    "Credentials": {{
+                        "SecurityToken": "token",
+                        "Expiration": "{expiration}",
+                        "AccessKeyId": "STS.{n}",
+                        "AccessKeySecret": "sts-secret"
+                    }}
+                }}"#
+            );
+            Ok(http::Response::builder()
+                .status(200)
+                .body(body)
+                .unwrap()
+                .into())
+        }
+    }
+
+    let calls = Arc::new(AtomicU32::new(0));
+    let fail = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let provider = |expires_in| {
+        let client = Client::builder()
+            .credentials_provider(Arc::new(StaticCredentialsProvider::new(Credentials::new(
+                "id", "secret", None, None,
+            ))))
+            .host("sts.cn-hangzhou.aliyuncs.com")
+            .retry_policy(RetryPolicy::disabled())
+            .http_client(MockTransport {
+                calls: calls.clone(),
+                expires_in,
+                fail: fail.clone(),
+            })
+            .build();
+        AssumeRoleCredentialsProvider::builder()
+            .client(client)
+            .role_arn("acs:ram::123:role/test")
+            .role_session_name("s")
+            .build()
+    };
+
+    // 并发的load只会调用一次AssumeRole，之后使用缓存
+    let p = provider(time::Duration::hours(1));
+    let (a, b, c) = tokio::join!(p.load(), p.load(), p.load());
+    assert_eq!(a.unwrap().access_key_id, "STS.1");
+    assert_eq!(b.unwrap().access_key_id, "STS.1");
+    assert_eq!(c.unwrap().access_key_id, "STS.1");
+    assert_eq!(p.load().await.unwrap().access_key_id, "STS.1");
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // 凭证在refresh_margin内过期，每次load都会刷新
+    let p = provider(time::Duration::minutes(2));
+    assert_eq!(p.load().await.unwrap().access_key_id, "STS.2");
+    assert_eq!(p.load().await.unwrap().access_key_id, "STS.3");
+
+    // 刷新失败时继续使用没有过期的凭证
+    fail.store(true, Ordering::SeqCst);
+    assert_eq!(p.load().await.unwrap().access_key_id, "STS.3");
+
+    // 没有缓存的凭证时返回错误
+    let p = provider(time::Duration::hours(1));
+    assert!(p.load().await.is_err());
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn chained_assume_role_test() {
+    use crate::credentials::StaticCredentialsProvider;
+    use u_sdk_common::transport::HttpTransport;
+
+    // 根据扮演的角色返回不同的凭证，并检查使用的凭证
+    struct MockTransport;
+    #[async_trait::async_trait]
+    impl HttpTransport for MockTransport {
+        async fn execute(&self, request: reqwest::Request) -> Result<reqwest::Response, Error> {
+            let query = request.url().query().unwrap();
+            let authorization = request.headers()["authorization"].to_str().unwrap();
+            let token = request.headers().get("x-acs-security-token");
+            let n = if query.contains("role%2Fhop") {
+                assert!(authorization.starts_with("ACS3-HMAC-SHA256 Credential=id,"));
+                assert!(token.is_none());
+                1
+            } else {
+                assert!(authorization.starts_with("ACS3-HMAC-SHA256 Credential=STS.1,"));
+                assert_eq!(token.unwrap(), "token-1");
+                2
+            };
+            let body = format!(
+                r#"{{
+                    "RequestId": "req-{n}",
+                    "AssumedRoleUser": {{"AssumedRoleId": "role-id:s", "Arn": "acs:ram::123:role/test/s"}},
+                    "Credentials": {{
+                        "SecurityToken": "token-{n}",
+                        "Expiration": "2999-01-01T00:00:00Z",
+                        "AccessKeyId": "STS.{n}",
+                        "AccessKeySecret": "sts-secret"
+                    }}
+                }}"#
+            );
+            Ok(http::Response::builder()
+                .status(200)
+                .body(body)
+                .unwrap()
+                .into())
+        }
+    }
+
+    let client = Client::builder()
+        .credentials_provider(Arc::new(StaticCredentialsProvider::new(Credentials::new(
+            "id", "secret", None, None,
+        ))))
+        .host("sts.cn-hangzhou.aliyuncs.com")
+        .http_client(MockTransport)
+        .build();
+    let first = AssumeRoleCredentialsProvider::builder()
+        .client(client.clone())
+        .role_arn("acs:ram::123:role/hop")
+        .role_session_name("s")
+        .build();
+    let second = AssumeRoleCredentialsProvider::builder()
+        .client(client.with_credentials_provider(Arc::new(first)))
+        .role_arn("acs:ram::456:role/target")
+        .role_session_name("s")
+        .build();
+
+    let creds = second.load().await.unwrap();
+    assert_eq!(creds.access_key_id, "STS.2");
+    assert_eq!(
+        creds.sts_security_token.as_ref().map(|t| t.expose_secret()),
+        Some("token-2")
+    );
+    assert!(!creds.is_expired());
+}
@@ -0,0 +1,381 @@
+//! 离线评估权限策略，用于在部署前测试生成的策略是否允许或拒绝预期的请求
+
+use super::{ConditionBlock, ConditionValue, Effect, OneOrMany, Policy, Statement, condition_ops};
+use bon::Builder;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+/// 策略的评估结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// 有匹配的`Allow`语句，并且没有匹配的`Deny`语句
+    Allow,
+    /// 有匹配的`Deny`语句（显式拒绝），优先于`Allow`
+    Deny,
+    /// 没有匹配的语句（隐式拒绝）
+    ImplicitDeny,
+}
+
+/// 评估策略时的请求上下文
+///
+/// ```ignore
+/// let ctx = RequestContext::builder()
+///     .action("oss:PutObject")
+///     .resource("acs:oss:*:*:my-bucket/user/1/a.txt")
+///     .condition("acs:SourceIp", "192.168.1.10")
+///     .condition("acs:SecureTransport", true)
+///     .build();
+/// assert_eq!(policy.evaluate(&ctx), Decision::Allow);
+/// ```
+#[derive(Builder, Debug, Clone)]
+pub struct RequestContext {
+    // 条件键不区分大小写，保存为小写
+    #[builder(field)]
+    conditions: HashMap<String, Vec<String>>,
+    #[builder(into)]
+    action: String,
+    #[builder(into)]
+    resource: String,
+}
+
+impl<S: request_context_builder::State> RequestContextBuilder<S> {
+    /// 添加条件键的值，同一个条件键可以多次添加，作为多值的条件键
+    pub fn condition(mut self, key: &str, value: impl Into<ConditionValue>) -> Self {
+        self.conditions
+            .entry(key.to_lowercase())
+            .or_default()
+            .push(value.into().0);
+        self
+    }
+}
+
+impl Policy {
+    /// 按照RAM的规则评估请求：
+    /// - 有匹配的`Deny`语句时为[`Decision::Deny`]
+    /// - 否则有匹配的`Allow`语句时为[`Decision::Allow`]
+    /// - 都没有时为[`Decision::ImplicitDeny`]
+    ///
+    /// Action（不区分大小写）和Resource支持`*`、`?`通配符，支持`NotAction`，
+    /// 条件支持[`condition_ops`]中的所有运算符，不认识的运算符视为不满足：
+    /// - 请求中没有条件键时，取反的运算符（如`StringNotEquals`、`NotIpAddress`）满足，其它运算符不满足
+    /// - `Date*`运算符的值使用RFC 3339格式，如`2019-08-12T17:00:00+08:00`
+    /// - `IpAddress`不包括CIDR网段的第一个和最后一个地址，`IpAddressIncludeBorder`包括
+    pub fn evaluate(&self, ctx: &RequestContext) -> Decision {
+        let mut allowed = false;
+        for stmt in self.statement.iter().filter(|s| s.matches(ctx)) {
+            match stmt.effect {
+                Effect::Deny => return Decision::Deny,
+                Effect::Allow => allowed = true,
+            }
+        }
+        match allowed {
+            true => Decision::Allow,
+            false => Decision::ImplicitDeny,
+        }
+    }
+}
+
+impl Statement {
+    fn matches(&self, ctx: &RequestContext) -> bool {
+        let action = ctx.action.to_lowercase();
+        let action_matches =
+            |patterns: &OneOrMany<String>| any_glob(patterns, &action, str::to_lowercase);
+        let action_ok = match (&self.action, &self.not_action) {
+            (Some(actions), _) => action_matches(actions),
+            (None, Some(not_actions)) => !action_matches(not_actions),
+            (None, None) => false,
+        };
+        action_ok
+            && any_glob(&self.resource, &ctx.resource, str::to_owned)
+            && self.condition.as_ref().is_none_or(|c| c.matches(ctx))
+    }
+}
+
+fn any_glob(patterns: &OneOrMany<String>, text: &str, normalize: fn(&str) -> String) -> bool {
+    patterns
+        .as_slice()
+        .iter()
+        .any(|p| glob_match(&normalize(p), text))
+}
+
+impl ConditionBlock {
+    // 不同的运算符、条件键之间是“与”，同一个条件键的多个值之间是“或”
+    fn matches(&self, ctx: &RequestContext) -> bool {
+        self.0.iter().all(|(op, keys)| {
+            keys.iter().all(|(key, values)| {
+                let actual = ctx
+                    .conditions
+                    .get(&key.to_lowercase())
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                condition_matches(op, actual, values.as_slice())
+            })
+        })
+    }
+}
+
+fn condition_matches(op: &str, actual: &[String], expected: &[ConditionValue]) -> bool {
+    use condition_ops::*;
+
+    // 取反的运算符：请求中没有这个条件键时也满足
+    let (op, negated) = match op {
+        STRING_NOT_EQUALS => (STRING_EQUALS, true),
+        STRING_NOT_EQUALS_IGNORE_CASE => (STRING_EQUALS_IGNORE_CASE, true),
+        STRING_NOT_LIKE => (STRING_LIKE, true),
+        NUMERIC_NOT_EQUALS => (NUMERIC_EQUALS, true),
+        DATE_NOT_EQUALS => (DATE_EQUALS, true),
+        NOT_IP_ADDRESS => (IP_ADDRESS, true),
+        NOT_IP_ADDRESS_INCLUDE_BORDER => (IP_ADDRESS_INCLUDE_BORDER, true),
+        op => (op, false),
+    };
+    let compare: fn(&str, &str) -> Option<bool> = match op {
+        STRING_EQUALS => |a, e| Some(a == e),
+        STRING_EQUALS_IGNORE_CASE => |a, e| Some(a.eq_ignore_ascii_case(e)),
+        STRING_LIKE => |a, e| Some(glob_match(e, a)),
+        NUMERIC_EQUALS => |a, e| numeric(a, e, |a, e| a == e),
+        NUMERIC_LESS_THAN => |a, e| numeric(a, e, |a, e| a < e),
+        NUMERIC_LESS_THAN_EQUALS => |a, e| numeric(a, e, |a, e| a <= e),
+        NUMERIC_GREATER_THAN => |a, e| numeric(a, e, |a, e| a > e),
+        NUMERIC_GREATER_THAN_EQUALS => |a, e| numeric(a, e, |a, e| a >= e),
+        DATE_EQUALS => |a, e| date(a, e, |a, e| a == e),
+        DATE_LESS_THAN => |a, e| date(a, e, |a, e| a < e),
+        DATE_LESS_THAN_EQUALS => |a, e| date(a, e, |a, e| a <= e),
+        DATE_GREATER_THAN => |a, e| date(a, e, |a, e| a > e),
+        DATE_GREATER_THAN_EQUALS => |a, e| date(a, e, |a, e| a >= e),
+        BOOL => |a, e| Some(a.eq_ignore_ascii_case(e)),
+        IP_ADDRESS => |a, e| ip_in_range(a, e, false),
+        IP_ADDRESS_INCLUDE_BORDER => |a, e| ip_in_range(a, e, true),
+        _ => return false,
+    };
+
+    let matched = actual.iter().any(|a| {
+        expected
+            .iter()
+            .any(|e| compare(a, &e.0).unwrap_or_default())
+    });
+    matched != negated
+}
+
+fn numeric(a: &str, e: &str, cmp: fn(f64, f64) -> bool) -> Option<bool> {
+    Some(cmp(a.parse().ok()?, e.parse().ok()?))
+}
+
+fn date(a: &str, e: &str, cmp: fn(OffsetDateTime, OffsetDateTime) -> bool) -> Option<bool> {
+    let a = OffsetDateTime::parse(a, &Rfc3339).ok()?;
+    let e = OffsetDateTime::parse(e, &Rfc3339).ok()?;
+    Some(cmp(a, e))
+}
+
+/// `range`为IP地址或CIDR网段，`include_border`为false时不包括网段的第一个和最后一个地址
+fn ip_in_range(ip: &str, range: &str, include_border: bool) -> Option<bool> {
+    let ip: IpAddr = ip.parse().ok()?;
+    let (addr, prefix) = match range.split_once('/') {
+        Some((addr, prefix)) => (addr.parse::<IpAddr>().ok()?, Some(prefix.parse().ok()?)),
+        None => (range.parse::<IpAddr>().ok()?, None),
+    };
+    let (ip, addr, bits) = match (ip, addr) {
+        (IpAddr::V4(ip), IpAddr::V4(addr)) => (u32::from(ip) as u128, u32::from(addr) as u128, 32),
+        (IpAddr::V6(ip), IpAddr::V6(addr)) => (u128::from(ip), u128::from(addr), 128),
+        _ => return Some(false),
+    };
+    let prefix: u32 = prefix.unwrap_or(bits);
+    if prefix > bits {
+        return None;
+    }
+
+    let host_bits = bits - prefix;
+    let host_mask = u128::MAX.checked_shr(128 - host_bits).unwrap_or(0);
+    let first = addr & !host_mask;
+    let last = first | host_mask;
+    if ip < first || ip > last {
+        return Some(false);
+    }
+    // 只有1、2个地址的网段没有边界
+    Some(include_border || host_bits < 2 || (ip != first && ip != last))
+}
+
+/// 匹配`*`（任意个字符）和`?`（一个字符）通配符
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // 上一个`*`的位置，以及它匹配到的text位置
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                // 让`*`多匹配一个字符后重试
+                Some((sp, st)) => {
+                    p = sp + 1;
+                    t = st + 1;
+                    star = Some((sp, st + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[test]
+fn glob_and_ip_test() {
+    assert!(glob_match("oss:*", "oss:getobject"));
+    assert!(glob_match(
+        "acs:oss:*:*:b/user/1/*",
+        "acs:oss:*:*:b/user/1/a/b.txt"
+    ));
+    assert!(glob_match("a?c*", "abc"));
+    assert!(!glob_match(
+        "acs:oss:*:*:b/user/1/*",
+        "acs:oss:*:*:b/user/2/a"
+    ));
+    assert!(!glob_match("a?c", "ac"));
+
+    assert_eq!(
+        ip_in_range("192.168.1.10", "192.168.0.0/16", false),
+        Some(true)
+    );
+    assert_eq!(
+        ip_in_range("10.0.0.1", "192.168.0.0/16", false),
+        Some(false)
+    );
+    assert_eq!(ip_in_range("192.168.1.1", "192.168.1.1", false), Some(true));
+    assert_eq!(
+        ip_in_range("192.168.255.255", "192.168.0.0/16", false),
+        Some(false)
+    );
+    assert_eq!(
+        ip_in_range("192.168.255.255", "192.168.0.0/16", true),
+        Some(true)
+    );
+    assert_eq!(ip_in_range("1.2.3.4", "0.0.0.0/0", false), Some(true));
+    assert_eq!(
+        ip_in_range("2001:db8::1", "2001:db8::/32", false),
+        Some(true)
+    );
+    assert_eq!(ip_in_range("1.2.3.4", "2001:db8::/32", false), Some(false));
+    assert_eq!(ip_in_range("1.2.3.4", "1.2.3.0/33", false), None);
+}
+
+#[test]
+fn evaluate_test() {
+    use super::oss;
+
+    let mut policy = Policy::oss_write_prefix("b", "user/1/");
+    let mut cond = ConditionBlock::new();
+    cond.source_ip(ConditionValue::from("192.168.0.0/16"));
+    cond.insert(
+        condition_ops::DATE_LESS_THAN,
+        "acs:CurrentTime",
+        ConditionValue::from("2030-01-01T00:00:00+08:00"),
+    );
+    policy.statement[0].condition = Some(cond);
+    // 禁止使用HTTP
+    let mut cond = ConditionBlock::new();
+    cond.secure_transport(false);
+    policy.statement.push(Statement {
+        effect: Effect::Deny,
+        action: Some(oss::ALL.to_owned().into()),
+        not_action: None,
+        resource: "*".to_owned().into(),
+        condition: Some(cond),
+    });
+
+    let ctx = |action: &str, key: &str, ip: &str, secure: bool| {
+        RequestContext::builder()
+            .action(action)
+            .resource(format!("acs:oss:*:*:b/{}", key))
+            .condition("acs:SourceIp", ip)
+            .condition("acs:SecureTransport", secure)
+            .condition("acs:CurrentTime", "2025-06-01T12:00:00Z")
+            .build()
+    };
+    let eval = |action, key, ip, secure| policy.evaluate(&ctx(action, key, ip, secure));
+    assert_eq!(
+        eval("oss:PutObject", "user/1/a.txt", "192.168.1.10", true),
+        Decision::Allow
+    );
+    // Action不区分大小写
+    assert_eq!(
+        eval("OSS:putobject", "user/1/a.txt", "192.168.1.10", true),
+        Decision::Allow
+    );
+    assert_eq!(
+        eval("oss:PutObject", "user/2/a.txt", "192.168.1.10", true),
+        Decision::ImplicitDeny
+    );
+    assert_eq!(
+        eval("oss:GetObject", "user/1/a.txt", "192.168.1.10", true),
+        Decision::ImplicitDeny
+    );
+    assert_eq!(
+        eval("oss:PutObject", "user/1/a.txt", "10.0.0.1", true),
+        Decision::ImplicitDeny
+    );
+    // 显式拒绝优先
+    assert_eq!(
+        eval("oss:PutObject", "user/1/a.txt", "192.168.1.10", false),
+        Decision::Deny
+    );
+    // 请求中没有条件键时条件不满足
+    let no_ip = RequestContext::builder()
+        .action("oss:PutObject")
+        .resource("acs:oss:*:*:b/user/1/a.txt")
+        .build();
+    assert_eq!(policy.evaluate(&no_ip), Decision::ImplicitDeny);
+
+    // NotAction和取反的运算符
+    let mut cond = ConditionBlock::new();
+    cond.insert(
+        condition_ops::STRING_NOT_LIKE,
+        oss::PREFIX,
+        ConditionValue::from("private/*"),
+    );
+    cond.insert(
+        condition_ops::NUMERIC_LESS_THAN_EQUALS,
+        "oss:MaxKeys",
+        ConditionValue::from(100_i64),
+    );
+    let policy = Policy::builder()
+        .statement(Statement {
+            effect: Effect::Allow,
+            action: None,
+            not_action: Some(vec!["oss:Delete*".to_owned(), "oss:Put*".to_owned()].into()),
+            resource: "acs:oss:*:*:b*".to_owned().into(),
+            condition: Some(cond),
+        })
+        .unwrap()
+        .build();
+    let list = |prefix: &str, max_keys: i64| {
+        RequestContext::builder()
+            .action(oss::LIST_OBJECTS)
+            .resource("acs:oss:*:*:b")
+            .condition(oss::PREFIX, prefix)
+            .condition("oss:MaxKeys", max_keys)
+            .build()
+    };
+    assert_eq!(policy.evaluate(&list("public/", 100)), Decision::Allow);
+    assert_eq!(
+        policy.evaluate(&list("private/a", 100)),
+        Decision::ImplicitDeny
+    );
+    assert_eq!(
+        policy.evaluate(&list("public/", 1000)),
+        Decision::ImplicitDeny
+    );
+    let delete = RequestContext::builder()
+        .action(oss::DELETE_OBJECT)
+        .resource("acs:oss:*:*:b/a.txt")
+        .build();
+    assert_eq!(policy.evaluate(&delete), Decision::ImplicitDeny);
+}
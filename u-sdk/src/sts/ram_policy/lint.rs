@@ -0,0 +1,218 @@
+//! 检查权限策略中常见的问题
+
+use super::{Effect, Policy, PolicyValidationError, condition_ops};
+use std::fmt;
+
+/// `AssumeRole`的`Policy`参数（会话策略）的最大长度
+pub const MAX_SESSION_POLICY_LEN: usize = 1024;
+
+const KNOWN_OPERATORS: &[&str] = {
+    use condition_ops::*;
+    &[
+        STRING_EQUALS,
+        STRING_NOT_EQUALS,
+        STRING_EQUALS_IGNORE_CASE,
+        STRING_NOT_EQUALS_IGNORE_CASE,
+        STRING_LIKE,
+        STRING_NOT_LIKE,
+        NUMERIC_EQUALS,
+        NUMERIC_NOT_EQUALS,
+        NUMERIC_LESS_THAN,
+        NUMERIC_LESS_THAN_EQUALS,
+        NUMERIC_GREATER_THAN,
+        NUMERIC_GREATER_THAN_EQUALS,
+        DATE_EQUALS,
+        DATE_NOT_EQUALS,
+        DATE_LESS_THAN,
+        DATE_LESS_THAN_EQUALS,
+        DATE_GREATER_THAN,
+        DATE_GREATER_THAN_EQUALS,
+        BOOL,
+        IP_ADDRESS,
+        NOT_IP_ADDRESS,
+        IP_ADDRESS_INCLUDE_BORDER,
+        NOT_IP_ADDRESS_INCLUDE_BORDER,
+    ]
+};
+
+/// [`Policy::lint`]发现的问题，`statement`为语句在`Policy::statement`中的下标
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintWarning {
+    /// 语句不满足Action / NotAction二选一的约束
+    InvalidStatement {
+        statement: usize,
+        error: PolicyValidationError,
+    },
+    /// 不认识的条件运算符，RAM会拒绝这个策略
+    UnknownConditionOperator { statement: usize, operator: String },
+    /// Resource不是`*`，也不是`acs:<service>:<region>:<account>:<resource>`格式的ARN
+    MalformedArn { statement: usize, resource: String },
+    /// `Allow`语句的Action或Resource为`*`，授予的权限可能过大
+    OverlyBroad { statement: usize },
+    /// 序列化后的长度超过了[`MAX_SESSION_POLICY_LEN`]，作为`AssumeRole`的会话策略时会失败
+    SessionPolicyTooLong { len: usize },
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintWarning::InvalidStatement { statement, error } => {
+                write!(f, "statement {}: {}", statement, error)
+            }
+            LintWarning::UnknownConditionOperator {
+                statement,
+                operator,
+            } => write!(
+                f,
+                "statement {}: unknown condition operator `{}`",
+                statement, operator
+            ),
+            LintWarning::MalformedArn {
+                statement,
+                resource,
+            } => write!(f, "statement {}: malformed arn `{}`", statement, resource),
+            LintWarning::OverlyBroad { statement } => {
+                write!(f, "statement {}: allows `*` action or resource", statement)
+            }
+            LintWarning::SessionPolicyTooLong { len } => write!(
+                f,
+                "policy is {} characters long, exceeds the session policy limit of {}",
+                len, MAX_SESSION_POLICY_LEN
+            ),
+        }
+    }
+}
+
+impl Policy {
+    /// 检查策略中常见的问题，没有问题时返回空的`Vec`
+    ///
+    /// - 语句不满足Action / NotAction二选一的约束
+    /// - 不认识的条件运算符
+    /// - 格式错误的ARN
+    /// - Action或Resource为`*`的`Allow`语句
+    /// - 超过`AssumeRole`会话策略的长度限制（1024个字符）
+    pub fn lint(&self) -> Vec<LintWarning> {
+        let mut warnings = vec![];
+        for (i, stmt) in self.statement.iter().enumerate() {
+            if let Err(error) = stmt.validate() {
+                warnings.push(LintWarning::InvalidStatement {
+                    statement: i,
+                    error,
+                });
+            }
+
+            if let Some(cond) = &stmt.condition {
+                let mut operators: Vec<_> = cond
+                    .0
+                    .keys()
+                    .filter(|op| !KNOWN_OPERATORS.contains(&op.as_str()))
+                    .collect();
+                // HashMap的顺序不固定
+                operators.sort();
+                warnings.extend(operators.into_iter().map(|op| {
+                    LintWarning::UnknownConditionOperator {
+                        statement: i,
+                        operator: op.clone(),
+                    }
+                }));
+            }
+
+            for resource in stmt.resource.as_slice() {
+                if resource != "*" && !is_valid_arn(resource) {
+                    warnings.push(LintWarning::MalformedArn {
+                        statement: i,
+                        resource: resource.clone(),
+                    });
+                }
+            }
+
+            let is_star = |v: &String| v == "*";
+            let broad_action = stmt
+                .action
+                .as_ref()
+                .is_some_and(|a| a.as_slice().iter().any(is_star));
+            let broad_resource = stmt.resource.as_slice().iter().any(is_star);
+            if stmt.effect == Effect::Allow && (broad_action || broad_resource) {
+                warnings.push(LintWarning::OverlyBroad { statement: i });
+            }
+        }
+
+        // 序列化失败只可能是自定义的类型有问题，这里不会发生
+        if let Ok(json) = self.to_json_string() {
+            let len = json.chars().count();
+            if len > MAX_SESSION_POLICY_LEN {
+                warnings.push(LintWarning::SessionPolicyTooLong { len });
+            }
+        }
+        warnings
+    }
+}
+
+// acs:<service>:<region>:<account>:<relative_id>，region和account可以为空或`*`
+fn is_valid_arn(arn: &str) -> bool {
+    let parts: Vec<_> = arn.splitn(5, ':').collect();
+    matches!(
+        parts.as_slice(),
+        ["acs", service, _, _, relative_id] if !service.is_empty() && !relative_id.is_empty()
+    )
+}
+
+#[test]
+fn lint_test() {
+    let policy = Policy::oss_write_prefix("b", "user/1/");
+    assert_eq!(policy.lint(), vec![]);
+
+    let json = r#"{
+        "Version": "1",
+        "Statement": [
+            {
+                "Effect": "Allow",
+                "Action": "*",
+                "Resource": ["acs:oss:*:*:b", "oss:b/*", "acs:oss:*:*:"],
+                "Condition": {
+                    "StringEqual": {"oss:Prefix": "a"},
+                    "IpAddress": {"acs:SourceIp": "10.0.0.0/8"}
+                }
+            },
+            {"Effect": "Deny", "Resource": "*"},
+            {"Effect": "Deny", "Action": "oss:*", "Resource": "*"}
+        ]
+    }"#;
+    let warnings = Policy::from_json_str(json).unwrap().lint();
+    assert_eq!(
+        warnings,
+        vec![
+            LintWarning::UnknownConditionOperator {
+                statement: 0,
+                operator: "StringEqual".to_owned()
+            },
+            LintWarning::MalformedArn {
+                statement: 0,
+                resource: "oss:b/*".to_owned()
+            },
+            LintWarning::MalformedArn {
+                statement: 0,
+                resource: "acs:oss:*:*:".to_owned()
+            },
+            LintWarning::OverlyBroad { statement: 0 },
+            LintWarning::InvalidStatement {
+                statement: 1,
+                error: PolicyValidationError::MissingActionAndNotAction
+            },
+        ]
+    );
+    assert_eq!(
+        warnings[0].to_string(),
+        "statement 0: unknown condition operator `StringEqual`"
+    );
+
+    let resources: Vec<_> = (0..50)
+        .map(|i| format!("acs:oss:*:*:b/user/{}/*", i))
+        .collect();
+    let mut policy = Policy::oss_read_prefix("b", "user/");
+    policy.statement[0].resource = resources.into();
+    assert!(matches!(
+        policy.lint().as_slice(),
+        [LintWarning::SessionPolicyTooLong { len }] if *len > MAX_SESSION_POLICY_LEN
+    ));
+}
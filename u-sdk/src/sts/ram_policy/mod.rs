@@ -1,14 +1,29 @@
 //! 权限策略语言
 //!
 //! [官方文档](https://help.aliyun.com/zh/ram/policy-language/)
+//!
+//! 常用的OSS权限策略可以使用[`oss`]模块中的action常量、[`oss::OssArn`]和
+//! [`Policy::oss_read_prefix`]、[`Policy::oss_write_prefix`]构建，
+//! 构建的策略可以使用[`Policy::evaluate`]离线评估。
+//!
+//! 已有的json策略可以使用[`Policy::from_json_str`]解析，并使用[`Policy::lint`]检查常见的问题
+
+mod eval;
+mod lint;
+pub mod oss;
+
+pub use eval::{Decision, RequestContext};
+pub use lint::{LintWarning, MAX_SESSION_POLICY_LEN};
 
 use bon::Builder;
-use serde::Serialize;
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json;
 use std::collections::HashMap;
+use std::fmt;
 
 /// Version 目前只有 "1"
-#[derive(Debug, Default, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum PolicyVersion {
     #[serde(rename = "1")]
     #[default]
@@ -16,7 +31,7 @@ pub enum PolicyVersion {
 }
 
 /// Effect = "Allow" | "Deny"
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Effect {
     Allow,
     Deny,
@@ -24,13 +39,22 @@ pub enum Effect {
 
 /// JSON 中“可以是单值或数组”的通用包装：
 /// 比如 Action 可以是 "ecs:*" 或 ["ecs:*", "oss:*"]
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum OneOrMany<T> {
     One(T),
     Many(Vec<T>),
 }
 
+impl<T> OneOrMany<T> {
+    pub fn as_slice(&self) -> &[T] {
+        match self {
+            OneOrMany::One(value) => std::slice::from_ref(value),
+            OneOrMany::Many(values) => values,
+        }
+    }
+}
+
 impl<T> From<T> for OneOrMany<T> {
     fn from(value: T) -> Self {
         OneOrMany::One(value)
@@ -51,6 +75,47 @@ impl<T> From<Vec<T>> for OneOrMany<T> {
 #[serde(transparent)]
 pub struct ConditionValue(pub String);
 
+/// 反序列化时也接受json的数字和布尔值，如控制台中的`"kms:RecoveryWindowInDays": 10`
+impl<'de> Deserialize<'de> for ConditionValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ConditionValueVisitor;
+
+        impl Visitor<'_> for ConditionValueVisitor {
+            type Value = ConditionValue;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a string, number or boolean")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(ConditionValue::from(v))
+            }
+
+            fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+                Ok(ConditionValue(v))
+            }
+
+            fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+                Ok(ConditionValue::from(v))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(ConditionValue::from(v))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(ConditionValue::from(v))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                Ok(ConditionValue(v.to_string()))
+            }
+        }
+
+        deserializer.deserialize_any(ConditionValueVisitor)
+    }
+}
+
 impl From<String> for ConditionValue {
     fn from(s: String) -> Self {
         ConditionValue(s)
@@ -88,7 +153,7 @@ pub type ConditionMap =
     HashMap<ConditionOperator, HashMap<ConditionKey, OneOrMany<ConditionValue>>>;
 
 /// 条件块 Condition Block
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(transparent)]
 pub struct ConditionBlock(pub ConditionMap);
 
@@ -112,6 +177,20 @@ impl ConditionBlock {
         let entry = self.0.entry(op).or_default();
         entry.insert(key, values.into());
     }
+
+    /// 限制请求来源的IP地址或CIDR网段，如`192.168.0.0/16`
+    pub fn source_ip(&mut self, ips: impl Into<OneOrMany<ConditionValue>>) {
+        self.insert(condition_ops::IP_ADDRESS, condition_keys::SOURCE_IP, ips);
+    }
+
+    /// 限制是否必须使用HTTPS
+    pub fn secure_transport(&mut self, secure: bool) {
+        self.insert(
+            condition_ops::BOOL,
+            condition_keys::SECURE_TRANSPORT,
+            ConditionValue::from(secure),
+        );
+    }
 }
 
 impl Default for ConditionBlock {
@@ -156,6 +235,20 @@ pub mod condition_ops {
     pub const NOT_IP_ADDRESS_INCLUDE_BORDER: &str = "NotIpAddressIncludeBorder";
 }
 
+/// 常用的通用条件键，OSS的条件键见[`oss`]模块
+pub mod condition_keys {
+    /// 请求来源的IP地址，使用`IpAddress`、`NotIpAddress`运算符
+    pub const SOURCE_IP: &str = "acs:SourceIp";
+    /// 请求来源的VPC ID
+    pub const SOURCE_VPC: &str = "acs:SourceVpc";
+    /// 是否使用HTTPS，使用`Bool`运算符
+    pub const SECURE_TRANSPORT: &str = "acs:SecureTransport";
+    /// 是否使用MFA
+    pub const MFA_PRESENT: &str = "acs:MFAPresent";
+    /// 请求的时间，使用`Date*`运算符
+    pub const CURRENT_TIME: &str = "acs:CurrentTime";
+}
+
 /// 单条授权语句 Statement，
 /// 对应语法：
 /// ```txt
@@ -167,7 +260,7 @@ pub mod condition_ops {
 /// }
 /// ```
 #[serde_with::skip_serializing_none]
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Statement {
     pub effect: Effect,
@@ -186,18 +279,19 @@ pub struct Statement {
 ///   "Statement": [ <statement>, ... ]
 /// }
 /// ```
-#[derive(Builder, Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Builder, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Policy {
     #[builder(field)]
     pub statement: Vec<Statement>,
     #[builder(default)]
+    #[serde(default)]
     pub version: PolicyVersion,
 }
 
 // 校验 Action / NotAction 约束
 /// Policy 结构校验错误，只关注语法约束（Action/NotAction）
-#[derive(thiserror::Error, Debug)]
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
 pub enum PolicyValidationError {
     /// 既没有 Action 也没有 NotAction
     #[error("statement must contain either Action or NotAction")]
@@ -239,6 +333,17 @@ impl<S: policy_builder::State> PolicyBuilder<S> {
 }
 
 impl Policy {
+    /// 解析json格式的权限策略，如从文件或控制台复制的策略，可以使用[`Policy::validate`]校验，
+    /// 或使用[`Policy::lint`]检查常见的问题
+    pub fn from_json_str(s: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(s)
+    }
+
+    /// 校验所有语句是否满足Action / NotAction二选一的约束
+    pub fn validate(&self) -> Result<(), PolicyValidationError> {
+        self.statement.iter().try_for_each(Statement::validate)
+    }
+
     pub fn to_json_string(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string(self)
     }
@@ -301,3 +406,57 @@ fn build_policy_test() {
     let s = Policy::builder().statement(stmt).unwrap().build();
     println!("policy json:\n{}", s.to_json_string_pretty().unwrap());
 }
+
+#[test]
+fn deserialize_policy_test() {
+    // 控制台中的策略，条件值可以是数字、布尔值
+    let json = r#"{
+        "Version": "1",
+        "Statement": [
+            {
+                "Effect": "Allow",
+                "Action": ["oss:GetObject", "oss:ListObjects"],
+                "Resource": "acs:oss:*:*:b/*",
+                "Condition": {
+                    "Bool": {"acs:SecureTransport": true},
+                    "NumericLessThanEquals": {"oss:MaxKeys": [100, 1.5]},
+                    "IpAddress": {"acs:SourceIp": "10.0.0.0/8"}
+                }
+            },
+            {"Effect": "Deny", "NotAction": "oss:Get*", "Resource": ["*"]}
+        ]
+    }"#;
+    let policy = Policy::from_json_str(json).unwrap();
+    policy.validate().unwrap();
+    assert_eq!(policy.version, PolicyVersion::V1);
+    assert_eq!(policy.statement.len(), 2);
+
+    let cond = &policy.statement[0].condition.as_ref().unwrap().0;
+    assert_eq!(
+        cond["Bool"]["acs:SecureTransport"],
+        OneOrMany::One(ConditionValue::from(true))
+    );
+    assert_eq!(
+        cond["NumericLessThanEquals"]["oss:MaxKeys"],
+        OneOrMany::Many(vec!["100".into(), "1.5".into()])
+    );
+    assert_eq!(
+        policy.statement[1].not_action,
+        Some(OneOrMany::One("oss:Get*".to_owned()))
+    );
+    assert_eq!(
+        policy.statement[1].resource,
+        OneOrMany::Many(vec!["*".to_owned()])
+    );
+
+    // 序列化后可以再解析为相同的策略
+    let json = policy.to_json_string().unwrap();
+    assert_eq!(Policy::from_json_str(&json).unwrap(), policy);
+
+    let invalid = r#"{"Statement": [{"Effect": "Allow", "Resource": "*"}]}"#;
+    assert_eq!(
+        Policy::from_json_str(invalid).unwrap().validate(),
+        Err(PolicyValidationError::MissingActionAndNotAction)
+    );
+    assert!(Policy::from_json_str(r#"{"Statement": [{"Effect": "Maybe"}]}"#).is_err());
+}
@@ -0,0 +1,250 @@
+//! OSS的权限策略
+//!
+//! [OSS Action列表](https://help.aliyun.com/zh/oss/user-guide/ram-policy-elements)
+//!
+//! ```ignore
+//! // 只允许上传到`user/{id}/`下
+//! let policy = Policy::oss_write_prefix("my-bucket", format!("user/{}/", id));
+//! let res = sts_client
+//!     .assume_role()
+//!     .policy(policy)
+//!     ...
+//! ```
+
+use super::{
+    ConditionBlock, ConditionValue, Effect, OneOrMany, Policy, PolicyVersion, Statement,
+    condition_ops,
+};
+use std::fmt;
+
+// region:    --- actions
+/// 列举bucket
+pub const LIST_BUCKETS: &str = "oss:ListBuckets";
+/// 获取bucket的信息
+pub const GET_BUCKET_INFO: &str = "oss:GetBucketInfo";
+/// 获取bucket所在的region
+pub const GET_BUCKET_LOCATION: &str = "oss:GetBucketLocation";
+/// 列举object，可以使用[`PREFIX`]条件键限制列举的前缀，资源为bucket
+pub const LIST_OBJECTS: &str = "oss:ListObjects";
+/// 下载object，也用于`HeadObject`、`GetObjectMeta`
+pub const GET_OBJECT: &str = "oss:GetObject";
+/// 上传object，也用于`AppendObject`、`PostObject`和分片上传的`InitiateMultipartUpload`、
+/// `UploadPart`、`CompleteMultipartUpload`
+pub const PUT_OBJECT: &str = "oss:PutObject";
+/// 删除object，也用于`DeleteMultipleObjects`
+pub const DELETE_OBJECT: &str = "oss:DeleteObject";
+/// 取消分片上传
+pub const ABORT_MULTIPART_UPLOAD: &str = "oss:AbortMultipartUpload";
+/// 列举已上传的分片
+pub const LIST_PARTS: &str = "oss:ListParts";
+/// 列举进行中的分片上传，资源为bucket
+pub const LIST_MULTIPART_UPLOADS: &str = "oss:ListMultipartUploads";
+pub const GET_OBJECT_ACL: &str = "oss:GetObjectAcl";
+pub const PUT_OBJECT_ACL: &str = "oss:PutObjectAcl";
+pub const GET_OBJECT_TAGGING: &str = "oss:GetObjectTagging";
+pub const PUT_OBJECT_TAGGING: &str = "oss:PutObjectTagging";
+pub const DELETE_OBJECT_TAGGING: &str = "oss:DeleteObjectTagging";
+/// 解冻归档、冷归档object
+pub const RESTORE_OBJECT: &str = "oss:RestoreObject";
+/// 所有OSS操作
+pub const ALL: &str = "oss:*";
+// endregion: --- actions
+
+// region:    --- condition keys
+/// `ListObjects`的`prefix`参数，使用`StringLike`等字符串运算符
+pub const PREFIX: &str = "oss:Prefix";
+/// `ListObjects`的`delimiter`参数
+pub const DELIMITER: &str = "oss:Delimiter";
+// endregion: --- condition keys
+
+/// OSS资源的ARN：`acs:oss:{region}:{account_id}:{bucket}[/{object}]`
+///
+/// region和account_id默认为`*`
+///
+/// ```ignore
+/// // acs:oss:*:*:my-bucket
+/// OssArn::bucket("my-bucket");
+/// // acs:oss:*:*:my-bucket/user/1/*
+/// OssArn::bucket("my-bucket").prefix("user/1/");
+/// // acs:oss:*:123:my-bucket/a.txt
+/// OssArn::bucket("my-bucket").object("a.txt").account_id("123");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OssArn {
+    region: String,
+    account_id: String,
+    bucket: String,
+    object: Option<String>,
+}
+
+impl OssArn {
+    /// bucket本身，用于`ListObjects`等bucket级别的操作
+    pub fn bucket(bucket: impl Into<String>) -> Self {
+        Self {
+            region: "*".to_owned(),
+            account_id: "*".to_owned(),
+            bucket: bucket.into(),
+            object: None,
+        }
+    }
+
+    /// bucket中的某个object，可以包含`*`通配符
+    pub fn object(mut self, key: impl Into<String>) -> Self {
+        self.object = Some(key.into());
+        self
+    }
+
+    /// bucket中以`prefix`开头的所有object，即`{prefix}*`
+    pub fn prefix(self, prefix: impl Into<String>) -> Self {
+        let key = format!("{}*", prefix.into());
+        self.object(key)
+    }
+
+    /// bucket中的所有object，即`*`
+    pub fn all_objects(self) -> Self {
+        self.object("*")
+    }
+
+    pub fn region(mut self, region: impl Into<String>) -> Self {
+        self.region = region.into();
+        self
+    }
+
+    pub fn account_id(mut self, account_id: impl Into<String>) -> Self {
+        self.account_id = account_id.into();
+        self
+    }
+}
+
+impl fmt::Display for OssArn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "acs:oss:{}:{}:{}",
+            self.region, self.account_id, self.bucket
+        )?;
+        if let Some(object) = &self.object {
+            write!(f, "/{}", object)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<OssArn> for String {
+    fn from(arn: OssArn) -> Self {
+        arn.to_string()
+    }
+}
+
+impl From<OssArn> for OneOrMany<String> {
+    fn from(arn: OssArn) -> Self {
+        OneOrMany::One(arn.to_string())
+    }
+}
+
+impl ConditionBlock {
+    /// 限制`ListObjects`只能列举以`prefix`开头的object，即`StringLike oss:Prefix {prefix}*`
+    pub fn oss_prefix(&mut self, prefix: &str) {
+        self.insert(
+            condition_ops::STRING_LIKE,
+            PREFIX,
+            ConditionValue(format!("{}*", prefix)),
+        );
+    }
+}
+
+fn allow(actions: &[&str], resource: OssArn, condition: Option<ConditionBlock>) -> Statement {
+    Statement {
+        effect: Effect::Allow,
+        action: Some(OneOrMany::Many(
+            actions.iter().map(|a| a.to_string()).collect(),
+        )),
+        not_action: None,
+        resource: resource.into(),
+        condition,
+    }
+}
+
+impl Policy {
+    /// 只允许下载`bucket`中以`prefix`开头的object，并列举这个前缀下的object
+    pub fn oss_read_prefix(bucket: &str, prefix: &str) -> Self {
+        let mut cond = ConditionBlock::new();
+        cond.oss_prefix(prefix);
+        Self {
+            statement: vec![
+                allow(&[GET_OBJECT], OssArn::bucket(bucket).prefix(prefix), None),
+                allow(&[LIST_OBJECTS], OssArn::bucket(bucket), Some(cond)),
+            ],
+            version: PolicyVersion::V1,
+        }
+    }
+
+    /// 只允许上传到`bucket`中以`prefix`开头的object，包括分片上传，不允许下载和删除
+    pub fn oss_write_prefix(bucket: &str, prefix: &str) -> Self {
+        Self {
+            statement: vec![allow(
+                &[PUT_OBJECT, ABORT_MULTIPART_UPLOAD, LIST_PARTS],
+                OssArn::bucket(bucket).prefix(prefix),
+                None,
+            )],
+            version: PolicyVersion::V1,
+        }
+    }
+}
+
+#[test]
+fn oss_policy_test() {
+    use serde_json::json;
+
+    assert_eq!(OssArn::bucket("b").to_string(), "acs:oss:*:*:b");
+    assert_eq!(
+        OssArn::bucket("b").prefix("user/1/").to_string(),
+        "acs:oss:*:*:b/user/1/*"
+    );
+    assert_eq!(
+        OssArn::bucket("b")
+            .object("a.txt")
+            .region("oss-cn-hangzhou")
+            .account_id("123")
+            .to_string(),
+        "acs:oss:oss-cn-hangzhou:123:b/a.txt"
+    );
+
+    let policy = Policy::oss_read_prefix("b", "user/1/");
+    assert_eq!(
+        serde_json::to_value(&policy).unwrap(),
+        json!({
+            "Version": "1",
+            "Statement": [
+                {"Effect": "Allow", "Action": ["oss:GetObject"], "Resource": "acs:oss:*:*:b/user/1/*"},
+                {
+                    "Effect": "Allow",
+                    "Action": ["oss:ListObjects"],
+                    "Resource": "acs:oss:*:*:b",
+                    "Condition": {"StringLike": {"oss:Prefix": "user/1/*"}}
+                }
+            ]
+        })
+    );
+
+    let mut policy = Policy::oss_write_prefix("b", "user/1/");
+    let mut cond = ConditionBlock::new();
+    cond.source_ip(vec!["10.0.0.0/8".into(), "192.168.1.1".into()]);
+    cond.secure_transport(true);
+    policy.statement[0].condition = Some(cond);
+    assert_eq!(
+        serde_json::to_value(&policy).unwrap(),
+        json!({
+            "Version": "1",
+            "Statement": [{
+                "Effect": "Allow",
+                "Action": ["oss:PutObject", "oss:AbortMultipartUpload", "oss:ListParts"],
+                "Resource": "acs:oss:*:*:b/user/1/*",
+                "Condition": {
+                    "IpAddress": {"acs:SourceIp": ["10.0.0.0/8", "192.168.1.1"]},
+                    "Bool": {"acs:SecureTransport": "true"}
+                }
+            }]
+        })
+    );
+}
@@ -1,7 +1,11 @@
 use super::Client;
 use super::ram_policy::Policy;
+use crate::credentials::cache::parse_expiration;
 use bon::Builder;
 use serde::{Deserialize, Serialize, Serializer};
+use time::OffsetDateTime;
+use u_sdk_common::Error;
+use u_sdk_common::secret::Secret;
 
 /// [AssumRole API](https://help.aliyun.com/zh/ram/developer-reference/api-sts-2015-04-01-assumerole)
 #[serde_with::skip_serializing_none]
@@ -34,6 +38,55 @@ where
     }
 }
 
+/// [AssumeRoleWithSAML API](https://help.aliyun.com/zh/ram/developer-reference/api-sts-2015-04-01-assumerolewithsaml)
+///
+/// 匿名调用，不需要client的`credentials_provider`
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Builder)]
+#[serde(rename_all = "PascalCase")]
+pub struct AssumeRoleWithSAML<'a> {
+    #[serde(skip_serializing)]
+    #[builder(start_fn)]
+    pub(crate) client: &'a Client,
+    #[serde(rename = "SAMLProviderArn")]
+    saml_provider_arn: &'a str,
+    role_arn: &'a str,
+    /// IdP返回的Base64编码的SAML断言
+    #[serde(rename = "SAMLAssertion")]
+    saml_assertion: &'a str,
+    #[serde(serialize_with = "policy_as_string")]
+    policy: Option<Policy>,
+    duration_seconds: Option<u32>,
+}
+
+/// [AssumeRoleWithOIDC API](https://help.aliyun.com/zh/ram/developer-reference/api-sts-2015-04-01-assumerolewithoidc)
+///
+/// 匿名调用，不需要client的`credentials_provider`
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Builder)]
+#[serde(rename_all = "PascalCase")]
+pub struct AssumeRoleWithOIDC<'a> {
+    #[serde(skip_serializing)]
+    #[builder(start_fn)]
+    pub(crate) client: &'a Client,
+    #[serde(rename = "OIDCProviderArn")]
+    oidc_provider_arn: &'a str,
+    role_arn: &'a str,
+    #[serde(rename = "OIDCToken")]
+    oidc_token: &'a str,
+    #[serde(serialize_with = "policy_as_string")]
+    policy: Option<Policy>,
+    duration_seconds: Option<u32>,
+    role_session_name: Option<&'a str>,
+}
+
+/// [GetCallerIdentity API](https://help.aliyun.com/zh/ram/developer-reference/api-sts-2015-04-01-getcalleridentity)
+#[derive(Builder)]
+pub struct GetCallerIdentity<'a> {
+    #[builder(start_fn)]
+    pub(crate) client: &'a Client,
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "PascalCase")]
 pub struct AssumeRoleResponse {
@@ -50,11 +103,113 @@ pub struct AssumedRoleUser {
     pub arn: String,
 }
 
+/// `security_token`和`access_key_secret`使用[`Secret`]保存，`Debug`输出时显示为`***`
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "PascalCase")]
 pub struct Credentials {
-    pub security_token: String,
+    pub security_token: Secret,
     pub expiration: String,
     pub access_key_id: String,
-    pub access_key_secret: String,
+    pub access_key_secret: Secret,
+}
+
+impl Credentials {
+    /// 解析`expiration`，如`2015-04-09T11:52:19Z`
+    pub fn expires_at(&self) -> Result<OffsetDateTime, Error> {
+        parse_expiration(&self.expiration)
+    }
+}
+
+/// 转换为可以在各个client中使用的[`crate::credentials::Credentials`]，`expiration`解析失败时返回错误
+impl TryFrom<Credentials> for crate::credentials::Credentials {
+    type Error = Error;
+
+    fn try_from(creds: Credentials) -> Result<Self, Self::Error> {
+        let expires_at = creds.expires_at()?;
+        Ok(Self {
+            access_key_id: creds.access_key_id,
+            access_key_secret: creds.access_key_secret,
+            sts_security_token: Some(creds.security_token),
+            expires_at: Some(expires_at),
+        })
+    }
+}
+
+impl TryFrom<AssumeRoleResponse> for crate::credentials::Credentials {
+    type Error = Error;
+
+    fn try_from(res: AssumeRoleResponse) -> Result<Self, Self::Error> {
+        res.credentials.try_into()
+    }
+}
+
+impl TryFrom<AssumeRoleWithSAMLResponse> for crate::credentials::Credentials {
+    type Error = Error;
+
+    fn try_from(res: AssumeRoleWithSAMLResponse) -> Result<Self, Self::Error> {
+        res.credentials.try_into()
+    }
+}
+
+impl TryFrom<AssumeRoleWithOIDCResponse> for crate::credentials::Credentials {
+    type Error = Error;
+
+    fn try_from(res: AssumeRoleWithOIDCResponse) -> Result<Self, Self::Error> {
+        res.credentials.try_into()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct AssumeRoleWithSAMLResponse {
+    pub request_id: String,
+    pub assumed_role_user: AssumedRoleUser,
+    pub credentials: Credentials,
+    #[serde(rename = "SAMLAssertionInfo")]
+    pub saml_assertion_info: SAMLAssertionInfo,
+    pub source_identity: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct SAMLAssertionInfo {
+    pub subject_type: String,
+    pub subject: String,
+    pub recipient: String,
+    pub issuer: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct AssumeRoleWithOIDCResponse {
+    pub request_id: String,
+    pub assumed_role_user: AssumedRoleUser,
+    pub credentials: Credentials,
+    #[serde(rename = "OIDCTokenInfo")]
+    pub oidc_token_info: OIDCTokenInfo,
+    pub source_identity: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct OIDCTokenInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub client_ids: String,
+    pub expiration_time: String,
+    pub issuance_time: String,
+    pub verification_info: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct GetCallerIdentityResponse {
+    pub request_id: String,
+    /// `Account`、`RAMUser`或`AssumedRoleUser`
+    pub identity_type: String,
+    pub account_id: String,
+    pub principal_id: String,
+    pub arn: String,
+    pub user_id: Option<String>,
+    pub role_id: Option<String>,
 }
@@ -2,34 +2,54 @@
 
 use bon::bon;
 use std::sync::Arc;
+use std::time::Duration;
 
-mod error;
-pub use error::Error;
+pub use u_sdk_common::Error;
 
 mod trans;
 mod types_rs;
-mod utils;
 
-use crate::credentials::CredentialsProvider;
+use crate::credentials::{CredentialsProvider, DefaultCredentialsChain};
 pub use types_rs::*;
-use u_sdk_common::open_api_sign::OpenApiStyle;
+use u_sdk_common::open_api::OpenApiClient;
+use u_sdk_common::retry::RetryPolicy;
+use u_sdk_common::transport::HttpTransport;
 
 pub struct Client {
-    credentials_provider: Arc<dyn CredentialsProvider>,
-    http_client: reqwest::Client,
-    host: String,
-    style: OpenApiStyle,
+    api: OpenApiClient,
 }
 
 #[bon]
 impl Client {
+    /// - `credentials_provider`：默认为[`DefaultCredentialsChain`]
+    /// - `http_client`：`reqwest::Client`或其它[`HttpTransport`]，默认为`reqwest::Client::new()`
+    /// - `retry_policy`：重试策略，默认为[`RetryPolicy::default`]
+    /// - `connect_timeout`、`read_timeout`、`timeout`：连接、读取和单次请求的总超时，
+    ///   只在没有指定`http_client`时生效，默认不限制
     #[builder(on(String, into))]
-    pub fn new(credentials_provider: Arc<dyn CredentialsProvider>, host: String) -> Self {
+    pub fn new(
+        #[builder(
+            default = Arc::new(DefaultCredentialsChain::new())
+        )]
+        credentials_provider: Arc<dyn CredentialsProvider>,
+        host: String,
+        #[builder(with = |http_client: impl HttpTransport + 'static| Arc::new(http_client))]
+        http_client: Option<Arc<dyn HttpTransport>>,
+        #[builder(default)] retry_policy: RetryPolicy,
+        connect_timeout: Option<Duration>,
+        read_timeout: Option<Duration>,
+        timeout: Option<Duration>,
+    ) -> Self {
         Self {
-            credentials_provider,
-            http_client: reqwest::Client::new(),
-            host,
-            style: OpenApiStyle::RPC,
+            api: OpenApiClient::builder()
+                .credentials_provider(credentials_provider)
+                .host(host)
+                .maybe_http_client(http_client)
+                .retry_policy(retry_policy)
+                .maybe_connect_timeout(connect_timeout)
+                .maybe_read_timeout(read_timeout)
+                .maybe_timeout(timeout)
+                .build(),
         }
     }
 }
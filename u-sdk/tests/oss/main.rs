@@ -9,6 +9,7 @@ use time::OffsetDateTime;
 use tokio_stream::StreamExt;
 use u_sdk::credentials::{Credentials, CredentialsProvider};
 use u_sdk::oss;
+use u_sdk::oss::HTTPVerb;
 use u_sdk::oss::object::{CallBackBody, CallbackBodyType, OssCallBack};
 
 #[derive(Deserialize, Debug)]
@@ -94,6 +95,25 @@ async fn describe_regions_test() {
     }
 }
 
+#[tokio::test]
+#[ignore]
+async fn discover_endpoint_test() {
+    let client = get_oss_client();
+    let res = client
+        .discover_endpoint()
+        .accelerate_if_enabled(true)
+        .send()
+        .await;
+    match res {
+        Ok(c) => println!(
+            "[success] region: {}, endpoint: {}",
+            c.region(),
+            c.endpoint()
+        ),
+        Err(e) => println!("[error] {}", e),
+    }
+}
+
 #[tokio::test]
 #[ignore]
 async fn put_bucket_test() {
@@ -144,6 +164,101 @@ async fn get_bucket_info_test() {
     }
 }
 
+#[tokio::test]
+#[ignore]
+async fn bucket_handle_test() {
+    let client = get_oss_client();
+    let other = client.bucket_handle("utab-app");
+    let res = other.list_objects_v2().max_keys(10).build().send().await;
+    match res {
+        Ok(s) => println!("[success] res:\n{:#?}", s),
+        Err(e) => println!("[error] {}", e),
+    }
+
+    let res = other.get_bucket_stat().build().send().await;
+    match res {
+        Ok(stat) => println!("[success] bucket stat: {:#?}", stat),
+        Err(e) => println!("[error] {}", e),
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn signature_v1_test() {
+    let file_str = std::fs::read_to_string("tests/oss/config.toml").unwrap();
+    let conf = toml::from_str::<OssConfig>(&file_str).unwrap();
+    let creds_provider = Arc::new(OssCredsProvider::new(
+        conf.access_key_id,
+        conf.access_key_secret,
+        conf.sts_security_token,
+    ));
+    let client = oss::Client::builder()
+        .credentials_provider(creds_provider)
+        .endpoint(conf.endpoint)
+        .region(conf.region)
+        .bucket(conf.bucket_name)
+        .signature_version(oss::SignatureVersion::V1)
+        .build();
+
+    let res = client
+        .put_object()
+        .content_type("text/plain")
+        .x_meta("key", "value")
+        .build()
+        .send("test/v1.txt", PutObjectBody::Bytes(b"hello v1".to_vec()))
+        .await;
+    println!("put: {:#?}", res);
+
+    let res = client.head_object().build().send("test/v1.txt").await;
+    println!("head: {:#?}", res);
+
+    let res = client
+        .get_object()
+        .build()
+        .generate_presigned_url("test/v1.txt", 300)
+        .await;
+    println!("presigned url: {:#?}", res);
+
+    let res = client
+        .post_object()
+        .build()
+        .generate_policy(OffsetDateTime::now_utc() + Duration::from_secs(300))
+        .await
+        .map(|r| {
+            r.form_fields()
+                .into_iter()
+                .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                .collect::<Vec<_>>()
+        });
+    println!("post form fields: {:#?}", res);
+}
+
+#[tokio::test]
+#[ignore]
+async fn signer_test() {
+    let client = get_oss_client();
+    let file_str = std::fs::read_to_string("tests/oss/config.toml").unwrap();
+    let conf = toml::from_str::<OssConfig>(&file_str).unwrap();
+    let creds = Credentials::new(
+        conf.access_key_id,
+        conf.access_key_secret,
+        conf.sts_security_token,
+        None,
+    );
+
+    // sdk未封装的API：GetBucketTags
+    let http_client = reqwest::Client::new();
+    let url = format!("https://{}.{}/?tagging", client.bucket(), client.endpoint());
+    let mut req = http_client.get(url).build().unwrap();
+    client
+        .signer()
+        .sign_request(&creds, &mut req, Some(client.bucket()))
+        .unwrap();
+    let resp = http_client.execute(req).await.unwrap();
+    println!("status: {}", resp.status());
+    println!("body: {}", resp.text().await.unwrap());
+}
+
 #[tokio::test]
 #[ignore]
 async fn get_bucket_location_test() {
@@ -216,6 +331,32 @@ async fn put_object_test() {
     }
 }
 
+#[tokio::test]
+#[ignore]
+async fn put_object_signed_payload_test() {
+    let client = get_oss_client();
+
+    let res = client
+        .put_object()
+        .content_type("text/plain")
+        .payload_signing(oss::PayloadSigning::Signed)
+        .build()
+        .send(
+            "test/signed-payload.toml",
+            PutObjectBody::FilePath(Path::new("tests/oss/config.sample.toml")),
+        )
+        .await;
+    println!("file: {:#?}", res);
+
+    let res = client
+        .append_object()
+        .payload_signing(oss::PayloadSigning::Signed)
+        .build()
+        .send("test/signed-payload-append.txt", 0, b"hello".to_vec())
+        .await;
+    println!("append: {:#?}", res);
+}
+
 #[tokio::test]
 #[ignore]
 async fn put_object_presigned_url_test() {
@@ -403,6 +544,41 @@ async fn get_object_presigned_url_test() {
      */
 }
 
+#[tokio::test]
+#[ignore]
+async fn presign_test() {
+    let client = get_oss_client();
+    let head_url = client
+        .head_object()
+        .build()
+        .generate_presigned_url("test/t-sample.toml", 300)
+        .await;
+    println!("head: {:#?}", head_url);
+
+    let delete_url = client
+        .presign_delete_object("test/t-sample.toml", 300)
+        .await;
+    println!("delete: {:#?}", delete_url);
+
+    // 分片上传：服务端初始化分片上传后，为每个分片生成URL交给客户端上传
+    let part_url = client
+        .presign_upload_part("test/big-file.bin", "upload-id", 1, 300)
+        .await;
+    println!("upload part: {:#?}", part_url);
+
+    // 任意API：例如列举bucket中的object
+    let list_url = client
+        .presign()
+        .method(HTTPVerb::Get)
+        .query("list-type", "2")
+        .query("prefix", "test/")
+        .expires(300)
+        .build()
+        .generate_presigned_url()
+        .await;
+    println!("list objects: {:#?}", list_url);
+}
+
 #[tokio::test]
 #[ignore]
 async fn copy_object_test() {
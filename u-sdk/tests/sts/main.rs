@@ -97,3 +97,44 @@ async fn assume_role_test() {
         Err(e) => println!("[error] {:#?}", e),
     }
 }
+
+#[tokio::test]
+#[ignore]
+async fn get_caller_identity_test() {
+    let client = get_sts_client();
+    let res = client.get_caller_identity().build().send().await;
+
+    match res {
+        Ok(s) => println!("[success] res:\n{:#?}", s),
+        Err(e) => println!("[error] {:#?}", e),
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn open_api_client_test() {
+    use u_sdk::open_api::OpenApiClient;
+
+    let conf_str = std::fs::read_to_string("tests/sts/config.toml").unwrap();
+    let conf = toml::from_str::<STSConfig>(&conf_str).unwrap();
+    let provider = STSCredsProvider::new(
+        conf.access_key_id,
+        conf.access_key_secret,
+        conf.sts_security_token,
+    );
+    let client = OpenApiClient::builder()
+        .credentials_provider(Arc::new(provider))
+        .host("sts.cn-hangzhou.aliyuncs.com")
+        .build();
+
+    let res = client
+        .call::<serde_json::Value>(
+            "GetCallerIdentity",
+            "2015-04-01",
+            reqwest::Method::POST,
+            std::collections::HashMap::<&str, &str>::new(),
+            None,
+        )
+        .await;
+    println!("res: {:#?}", res);
+}